@@ -0,0 +1,141 @@
+use std::io::Write;
+
+use anyhow::{anyhow, bail, Context};
+use space::cli::{csv_field, load_scenario, PropagationArgs, PROPAGATION_OPTIONS};
+
+fn usage() -> String {
+    format!(
+        "\
+usage: space-propagate <bodies.json> (--until <date> | --duration <seconds>) [options]
+
+    --until <date>          propagate up to the RFC 3339 date, e.g. 2030-01-01T00:00:00Z,
+                            dates before the scenario start propagate backward
+    --duration <seconds>    propagate for the amount of simulated seconds, negative to go backward
+{PROPAGATION_OPTIONS}
+    --format <json|csv>     output format [json]
+    --output <path>         output file, stdout if omitted"
+    )
+}
+
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+struct Args {
+    propagation: PropagationArgs,
+    format: OutputFormat,
+    output: Option<String>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = std::env::args().skip(1);
+
+    let mut parsed = Args {
+        propagation: PropagationArgs::default(),
+        format: OutputFormat::Json,
+        output: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for [{arg}]\n\n{}", usage()))
+        };
+
+        if parsed.propagation.parse(&arg, &mut value)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "json" => OutputFormat::Json,
+                    "csv" => OutputFormat::Csv,
+                    format => bail!("Unknown format: [{format}]\n\n{}", usage()),
+                }
+            }
+            "--output" => parsed.output = Some(value()?),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                std::process::exit(0);
+            }
+            _ => bail!("Unexpected argument: [{arg}]\n\n{}", usage()),
+        }
+    }
+
+    parsed
+        .propagation
+        .check()
+        .map_err(|e| anyhow!("{e}\n\n{}", usage()))?;
+
+    Ok(parsed)
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    let (bodies, mut simulation) = load_scenario(&args.propagation.input)?;
+
+    let target = args.propagation.target(simulation.time);
+
+    // target before the scenario start propagates backward in time
+    for collision in simulation.propagate_to(&args.propagation.params, target) {
+        eprintln!(
+            "{}: [{}] absorbed [{}]",
+            collision.time, collision.survivor_name, collision.absorbed_name
+        );
+    }
+
+    let results = bodies
+        .into_iter()
+        .filter_map(|body| {
+            let id = simulation.bodies.find(&body.name)?;
+
+            let mut body = body;
+            body.date = simulation.time;
+            body.body = simulation.bodies.to_body(id)?;
+
+            Some(body)
+        })
+        .collect::<Vec<_>>();
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            std::fs::File::create(path).with_context(|| format!("Failed to create [{path}]"))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+
+    match args.format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut output, &results)?;
+            writeln!(output)?;
+        }
+        OutputFormat::Csv => {
+            writeln!(output, "name,date,x,y,z,vx,vy,vz,mass,radius")?;
+
+            for body in &results {
+                let p = body.body.position;
+                let v = body.body.velocity;
+
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    csv_field(&body.name),
+                    body.date.to_rfc3339(),
+                    p.x,
+                    p.y,
+                    p.z,
+                    v.x,
+                    v.y,
+                    v.z,
+                    body.body.mass,
+                    body.body.radius
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use std::{collections::BTreeMap, io::Write};
+
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Timelike, Utc};
+use space::{
+    cli::{
+        csv_field, load_scenario, parse_value, read_bodies, PropagationArgs, PROPAGATION_OPTIONS,
+    },
+    nasa_horizons::{get_body_state_using_nasa_horizons, NasaBodyAddition},
+    simulation::dominant_body,
+};
+
+fn usage() -> String {
+    format!(
+        "\
+usage: space-validate <bodies.json> (--until <date> | --duration <seconds>) (--reference <path> | --fetch) [options]
+
+    --until <date>          validate up to the RFC 3339 date, e.g. 2030-01-01T00:00:00Z
+    --duration <seconds>    validate over the amount of simulated seconds, negative to go backward
+    --reference <path>      reference states in the format of <bodies.json> at other dates,
+                            e.g. saved from NASA Horizons or by space-propagate, may be repeated
+    --fetch                 query NASA Horizons for the bodies at the sampled epochs
+    --samples <n>           epochs to fetch, evenly spaced over the span [8]
+    --cache <path>          fetched states are read from and saved to the file
+{PROPAGATION_OPTIONS}
+    --format <table|csv>    output format [table]
+    --output <path>         output file, stdout if omitted
+    --plot <path>           plot of errors over time as SVG"
+    )
+}
+
+const PLOT_COLORS: [&str; 8] = [
+    "#ffd700", "#87cefa", "#ff7f50", "#98fb98", "#da70d6", "#f0e68c", "#40e0d0", "#ff69b4",
+];
+
+enum OutputFormat {
+    Table,
+    Csv,
+}
+
+struct Args {
+    propagation: PropagationArgs,
+    references: Vec<String>,
+    fetch: bool,
+    samples: usize,
+    cache: Option<String>,
+    format: OutputFormat,
+    output: Option<String>,
+    plot: Option<String>,
+}
+
+struct Deviation {
+    date: DateTime<Utc>,
+    name: String,
+    // meters
+    error: f64,
+    // relative to the distance from the dominant body
+    relative: f64,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = std::env::args().skip(1);
+
+    let mut parsed = Args {
+        propagation: PropagationArgs::default(),
+        references: vec![],
+        fetch: false,
+        samples: 8,
+        cache: None,
+        format: OutputFormat::Table,
+        output: None,
+        plot: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for [{arg}]\n\n{}", usage()))
+        };
+
+        if parsed.propagation.parse(&arg, &mut value)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--reference" => parsed.references.push(value()?),
+            "--fetch" => parsed.fetch = true,
+            "--samples" => parsed.samples = parse_value(&arg, &value()?)?,
+            "--cache" => parsed.cache = Some(value()?),
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "table" => OutputFormat::Table,
+                    "csv" => OutputFormat::Csv,
+                    format => bail!("Unknown format: [{format}]\n\n{}", usage()),
+                }
+            }
+            "--output" => parsed.output = Some(value()?),
+            "--plot" => parsed.plot = Some(value()?),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                std::process::exit(0);
+            }
+            _ => bail!("Unexpected argument: [{arg}]\n\n{}", usage()),
+        }
+    }
+
+    parsed
+        .propagation
+        .check()
+        .map_err(|e| anyhow!("{e}\n\n{}", usage()))?;
+
+    if parsed.references.is_empty() && !parsed.fetch {
+        bail!(
+            "At least one of --reference and --fetch is required\n\n{}",
+            usage()
+        );
+    }
+    if parsed.samples == 0 {
+        bail!("Samples must be positive");
+    }
+
+    Ok(parsed)
+}
+
+// states of the scenario bodies at the sampled epochs, except ones already in `cached`
+fn fetch_references(
+    bodies: &[NasaBodyAddition],
+    epochs: &[DateTime<Utc>],
+    cached: &[NasaBodyAddition],
+) -> Vec<NasaBodyAddition> {
+    let requests = epochs
+        .iter()
+        .flat_map(|date| bodies.iter().map(move |body| (*date, body)))
+        .filter(|(date, body)| {
+            !cached
+                .iter()
+                .any(|reference| reference.date == *date && reference.name == body.name)
+        })
+        .map(|(date, body)| async move {
+            let state = get_body_state_using_nasa_horizons(date, &body.name).await;
+
+            (date, body, state)
+        });
+
+    let responses = async_std::task::block_on(futures::future::join_all(requests));
+
+    responses
+        .into_iter()
+        .filter_map(|(date, body, state)| match state {
+            Ok((position, velocity)) => {
+                let mut body = body.clone();
+                body.date = date;
+                body.body.position = position;
+                body.body.velocity = velocity;
+
+                Some(body)
+            }
+            Err(e) => {
+                eprintln!("{date}: failed to fetch [{}]: {e}", body.name);
+                None
+            }
+        })
+        .collect()
+}
+
+fn write_plot(path: &str, start: DateTime<Utc>, deviations: &[Deviation]) -> anyhow::Result<()> {
+    const WIDTH: f64 = 960.0;
+    const HEIGHT: f64 = 540.0;
+    const MARGIN: f64 = 64.0;
+    const LEGEND: f64 = 160.0;
+
+    let days = |date: DateTime<Utc>| (date - start).num_seconds() as f64 / 86400.0;
+    // errors below a meter are drawn at the bottom
+    let log_error = |error: f64| (error / 1000.0).max(1e-3).log10();
+
+    let (min_day, max_day) = deviations
+        .iter()
+        .map(|deviation| days(deviation.date))
+        .fold((0f64, 0f64), |(min, max), day| (min.min(day), max.max(day)));
+    let min_log = deviations
+        .iter()
+        .map(|deviation| log_error(deviation.error).floor())
+        .fold(f64::INFINITY, f64::min)
+        .min(0.0);
+    let max_log = deviations
+        .iter()
+        .map(|deviation| log_error(deviation.error).ceil())
+        .fold(f64::NEG_INFINITY, f64::max)
+        .max(min_log + 1.0);
+
+    let x = |day: f64| {
+        MARGIN + (day - min_day) / (max_day - min_day).max(1e-9) * (WIDTH - 2.0 * MARGIN - LEGEND)
+    };
+    let y = |log: f64| {
+        HEIGHT - MARGIN - (log - min_log) / (max_log - min_log) * (HEIGHT - 2.0 * MARGIN)
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+         font-family=\"monospace\" font-size=\"12\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"black\"/>\n"
+    );
+
+    // decades of the error in kilometers
+    for decade in min_log as i32..=max_log as i32 {
+        let y = y(decade as f64);
+
+        svg += &format!(
+            "<line x1=\"{MARGIN}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"#333\"/>\n\
+             <text x=\"{}\" y=\"{}\" fill=\"white\" text-anchor=\"end\">1e{decade} km</text>\n",
+            WIDTH - MARGIN - LEGEND,
+            MARGIN - 6.0,
+            y + 4.0
+        );
+    }
+
+    for (day, anchor) in [(min_day, "start"), (max_day, "end")] {
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"white\" text-anchor=\"{anchor}\">{day:.1} d</text>\n",
+            x(day),
+            HEIGHT - MARGIN + 18.0
+        );
+    }
+
+    let mut names = deviations
+        .iter()
+        .map(|deviation| deviation.name.as_str())
+        .collect::<Vec<_>>();
+    names.dedup();
+
+    for (i, name) in names.iter().enumerate() {
+        let color = PLOT_COLORS[i % PLOT_COLORS.len()];
+
+        let mut points = deviations
+            .iter()
+            .filter(|deviation| deviation.name == *name)
+            .map(|deviation| (days(deviation.date), log_error(deviation.error)))
+            .collect::<Vec<_>>();
+        points.sort_by(|(d1, _), (d2, _)| d1.total_cmp(d2));
+
+        let points = points
+            .iter()
+            .map(|(day, log)| format!("{:.1},{:.1}", x(*day), y(*log)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        svg += &format!(
+            "<polyline points=\"{points}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\"/>\n\
+             <text x=\"{}\" y=\"{}\" fill=\"{color}\">{name}</text>\n",
+            WIDTH - LEGEND,
+            MARGIN + 16.0 * i as f64
+        );
+    }
+
+    svg += "</svg>\n";
+
+    std::fs::write(path, svg).with_context(|| format!("Failed to write [{path}]"))
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    let (bodies, simulation) = load_scenario(&args.propagation.input)?;
+
+    let earliest_time = simulation.time;
+    let target = args.propagation.target(earliest_time);
+
+    let mut references = vec![];
+
+    for path in &args.references {
+        references.extend(read_bodies(path)?);
+    }
+
+    if args.fetch {
+        let mut cached = match &args.cache {
+            Some(path) if std::path::Path::new(path).exists() => read_bodies(path)?,
+            _ => vec![],
+        };
+
+        // Horizons takes epochs in whole seconds
+        let epochs = (1..=args.samples)
+            .filter_map(|k| {
+                let epoch =
+                    earliest_time + (target - earliest_time) * k as i32 / args.samples as i32;
+                epoch.with_nanosecond(0)
+            })
+            .collect::<Vec<_>>();
+
+        let fetched = fetch_references(&bodies, &epochs, &cached);
+
+        if let Some(path) = &args.cache {
+            cached.extend(fetched);
+
+            std::fs::write(path, serde_json::to_string_pretty(&cached)?)
+                .with_context(|| format!("Failed to write [{path}]"))?;
+        } else {
+            cached = fetched;
+        }
+
+        references.extend(
+            cached
+                .into_iter()
+                .filter(|reference| epochs.contains(&reference.date)),
+        );
+    }
+
+    let mut epochs: BTreeMap<DateTime<Utc>, Vec<NasaBodyAddition>> = BTreeMap::new();
+
+    for reference in references {
+        epochs.entry(reference.date).or_default().push(reference);
+    }
+
+    let (backward, forward): (Vec<_>, Vec<_>) = epochs
+        .into_iter()
+        .partition(|(date, _)| *date < earliest_time);
+
+    let mut deviations = vec![];
+
+    // epochs before the scenario start are reached by propagating backward
+    for epochs in [forward, backward.into_iter().rev().collect()] {
+        let mut simulation = simulation.clone();
+
+        for (date, references) in epochs {
+            for collision in simulation.propagate_to(&args.propagation.params, date) {
+                eprintln!(
+                    "{}: [{}] absorbed [{}]",
+                    collision.time, collision.survivor_name, collision.absorbed_name
+                );
+            }
+
+            let dominant = dominant_body(simulation.bodies.masses());
+
+            for reference in references {
+                let Some(index) = simulation.bodies.try_get_index(&reference.name) else { continue };
+
+                let position = simulation.bodies.positions()[index];
+                let distance = dominant
+                    .filter(|dominant| *dominant != index)
+                    .map(|dominant| position.distance(simulation.bodies.positions()[dominant]))
+                    .unwrap_or(position.length());
+
+                let error = position.distance(reference.body.position);
+
+                deviations.push(Deviation {
+                    date,
+                    name: reference.name,
+                    error,
+                    relative: error / distance,
+                });
+            }
+        }
+    }
+
+    deviations.sort_by(|d1, d2| d1.name.cmp(&d2.name).then(d1.date.cmp(&d2.date)));
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            std::fs::File::create(path).with_context(|| format!("Failed to create [{path}]"))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+
+    match args.format {
+        OutputFormat::Table => {
+            writeln!(
+                output,
+                "{:<12} {:<20} {:>10} {:>14} {:>12}",
+                "name", "date", "days", "error, km", "relative"
+            )?;
+
+            for deviation in &deviations {
+                writeln!(
+                    output,
+                    "{:<12} {:<20} {:>10.2} {:>14.3} {:>12.3e}",
+                    deviation.name,
+                    deviation.date.format("%Y-%m-%d %H:%M:%S"),
+                    (deviation.date - earliest_time).num_seconds() as f64 / 86400.0,
+                    deviation.error / 1000.0,
+                    deviation.relative
+                )?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(output, "name,date,error,relative")?;
+
+            for deviation in &deviations {
+                writeln!(
+                    output,
+                    "{},{},{},{}",
+                    csv_field(&deviation.name),
+                    deviation.date.to_rfc3339(),
+                    deviation.error,
+                    deviation.relative
+                )?;
+            }
+        }
+    }
+
+    if let Some(path) = &args.plot {
+        write_plot(path, earliest_time, &deviations)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+pub mod space;
+pub use space::*;
@@ -1,5 +1,3 @@
-mod space;
-
 fn main() {
     use bevy::prelude::*;
 
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Duration, Utc};
+
+use super::{
+    nasa_horizons::{NasaBodyAddition, SpaceBodiesKnownDetails},
+    simulation::{SpaceSimulation, SpaceSimulationParams},
+};
+
+// options of `PropagationArgs` as they are listed in usage of the tools
+pub const PROPAGATION_OPTIONS: &str = "    \
+    --mode <name>           NBody, PatchedConics [NBody]
+    --step <seconds>        simulated seconds per step, same as the fixed step of the viewer [3600]
+    --precision <n>         substeps per step [4]
+    --integrator <name>     SemiImplicitEuler, VelocityVerlet, RungeKutta4, Yoshida4, DormandPrince45
+    --tolerance <value>     relative error tolerance of adaptive integrators [1e-9]
+    --force-backend <name>  Direct, BarnesHut [Direct]
+    --theta <value>         opening angle of the Barnes-Hut octree, smaller is more accurate [0.5]
+    --post-newtonian        add 1PN relativistic correction for the dominant mass
+    --zonal-harmonics <r>   add oblateness of known bodies within <r> of their radiuses
+    --no-non-gravitational  ignore Marsden non-gravitational forces of comets";
+
+// scenario, span and parameters of the simulation, given the same way to every tool
+#[derive(Default)]
+pub struct PropagationArgs {
+    pub input: String,
+    pub until: Option<DateTime<Utc>>,
+    pub duration: Option<f64>,
+    pub params: SpaceSimulationParams,
+}
+
+impl PropagationArgs {
+    // returns false if the argument is not a shared one, `value` takes the next argument
+    pub fn parse(
+        &mut self,
+        arg: &str,
+        mut value: impl FnMut() -> anyhow::Result<String>,
+    ) -> anyhow::Result<bool> {
+        match arg {
+            "--until" => {
+                let date = value()?;
+                self.until = Some(
+                    DateTime::parse_from_rfc3339(&date)
+                        .with_context(|| format!("Invalid date: [{date}]"))?
+                        .into(),
+                )
+            }
+            "--duration" => self.duration = Some(parse_value(arg, &value()?)?),
+            "--mode" => self.params.mode = parse_value(arg, &value()?)?,
+            "--step" => self.params.step = parse_value(arg, &value()?)?,
+            "--precision" | "--percision" => self.params.percision = parse_value(arg, &value()?)?,
+            "--integrator" => self.params.integrator = parse_value(arg, &value()?)?,
+            "--tolerance" => self.params.tolerance = parse_value(arg, &value()?)?,
+            "--force-backend" => self.params.force_backend = parse_value(arg, &value()?)?,
+            "--theta" => self.params.barnes_hut_theta = parse_value(arg, &value()?)?,
+            "--post-newtonian" => self.params.post_newtonian = true,
+            "--zonal-harmonics" => {
+                self.params.zonal_harmonics = true;
+                self.params.zonal_harmonics_distance = parse_value(arg, &value()?)?;
+            }
+            "--no-non-gravitational" => self.params.non_gravitational = false,
+            _ if self.input.is_empty() && !arg.starts_with('-') => self.input = arg.to_string(),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.input.is_empty() {
+            bail!("No input file");
+        }
+        if self.until.is_some() == self.duration.is_some() {
+            bail!("Exactly one of --until and --duration is required");
+        }
+        if self.params.step <= 0.0 || self.params.percision == 0 {
+            bail!("Step and precision must be positive");
+        }
+
+        Ok(())
+    }
+
+    // date the scenario starting at `start` is propagated to
+    pub fn target(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match (self.until, self.duration) {
+            (Some(until), _) => until,
+            (_, Some(duration)) => start + Duration::milliseconds((duration * 1000.0) as i64),
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn parse_value<T>(arg: &str, value: &str) -> anyhow::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| anyhow!("Invalid value [{value}] for [{arg}]: {e}"))
+}
+
+pub fn read_bodies(path: &str) -> anyhow::Result<Vec<NasaBodyAddition>> {
+    Ok(serde_json::from_str(
+        &std::fs::read_to_string(path).with_context(|| format!("Failed to read [{path}]"))?,
+    )?)
+}
+
+// same as loading bodies locally in the viewer, the simulation starts at the earliest date,
+// bodies are returned with coefficients of known bodies filled in
+pub fn load_scenario(path: &str) -> anyhow::Result<(Vec<NasaBodyAddition>, SpaceSimulation)> {
+    let mut bodies = read_bodies(path)?;
+
+    let earliest_time = bodies
+        .iter()
+        .map(|body| body.date)
+        .min()
+        .ok_or_else(|| anyhow!("No bodies in [{path}]"))?;
+
+    let mut simulation = SpaceSimulation {
+        time: earliest_time,
+        ..Default::default()
+    };
+
+    let known_details = SpaceBodiesKnownDetails::default();
+
+    for body in &mut bodies {
+        known_details.fill_zonal_harmonics(&body.name, &mut body.body);
+
+        simulation
+            .bodies
+            .insert(body.name.clone(), body.body.clone())?;
+    }
+
+    Ok((bodies, simulation))
+}
+
+// names may contain commas and quotes
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -9,7 +9,10 @@ pub mod systems {
     use crate::space::{
         display::BodyRef,
         ext::EntityOpsExt,
-        scene::markers::{FocusedBody, MainCamera3d, SelectedBody},
+        scene::{
+            markers::{FocusedBody, MainCamera3d, SelectedBody},
+            BodyPlacement,
+        },
     };
     use bevy::{
         input::mouse::{MouseMotion, MouseWheel},
@@ -69,10 +72,16 @@ pub mod systems {
         keyboard: Res<Input<ScanCode>>,
         mut commands: Commands,
         bodies: Query<Entity, With<BodyRef>>,
+        placement: Res<BodyPlacement>,
     ) {
         use FocusedBody::*;
         use SelectedBody::*;
 
+        // clicks place bodies around the focused one instead
+        if placement.active {
+            return;
+        }
+
         if mouse.just_pressed(MouseButton::Left) {
             if selected_body[CurrentRedirected].is_valid() {
                 if keyboard.pressed(ScanCode(29)) {
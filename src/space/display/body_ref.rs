@@ -1,4 +1,6 @@
 use bevy::prelude::*;
 
+use crate::space::simulation::BodyId;
+
 #[derive(Component)]
-pub struct BodyRef(pub String);
+pub struct BodyRef(pub BodyId);
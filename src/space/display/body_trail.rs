@@ -1,14 +1,27 @@
 use bevy::{math::DVec3, prelude::*};
 use ringbuffer::AllocRingBuffer;
 
-#[derive(Default, Component)]
+use crate::space::simulation::BodyId;
+
+#[derive(Component)]
 pub struct BodyTrail {
-    pub body_name: String,
-    pub anchor: Option<String>,
+    pub body: BodyId,
+    pub anchor: Option<BodyId>,
     pub last_anchor_position: DVec3,
     pub trail: AllocRingBuffer<DVec3>,
 }
 
+impl BodyTrail {
+    pub fn new(body: BodyId) -> Self {
+        Self {
+            body,
+            anchor: None,
+            last_anchor_position: DVec3::ZERO,
+            trail: default(),
+        }
+    }
+}
+
 pub mod systems {
     use bevy::{math::DVec3, prelude::*};
     use bevy_ecs_markers::params::Marker;
@@ -18,7 +31,9 @@ pub mod systems {
         display::{custom_params::ComputedScale, BodyRef, RelativeWorldOffset},
         ext::EntityOpsExt,
         scene::markers::FocusedBody,
-        simulation::SpaceSimulation,
+        simulation::{
+            SpaceSimulation, SpaceSimulationHistory, SpaceSimulationParams, SpaceSimulationSeeked,
+        },
     };
 
     use super::BodyTrail;
@@ -26,19 +41,29 @@ pub mod systems {
     pub fn extract_positions_from_simulations(
         mut body_trails: Query<&mut BodyTrail>,
         simulation: Res<SpaceSimulation>,
+        params: Res<SpaceSimulationParams>,
+        mut reversed: Local<bool>,
     ) {
+        // trail recorded in one direction would be retraced by the other one
+        if (params.speed < 0.0) != *reversed {
+            use ringbuffer::RingBufferExt;
+
+            *reversed = !*reversed;
+
+            for mut body_trail in &mut body_trails {
+                body_trail.trail.clear();
+            }
+        }
+
         for mut body_trail in &mut body_trails {
             use ringbuffer::RingBufferWrite;
 
-            let index = simulation.bodies.get_index(&body_trail.body_name);
+            let Some(index) = simulation.bodies.index(body_trail.body) else { continue };
             let position = simulation.bodies.positions()[index];
             body_trail.last_anchor_position = body_trail
                 .anchor
-                .as_ref()
-                .map(|i| {
-                    let index = simulation.bodies.get_index(i);
-                    simulation.bodies.positions()[index]
-                })
+                .and_then(|anchor| simulation.bodies.index(anchor))
+                .map(|index| simulation.bodies.positions()[index])
                 .unwrap_or(DVec3::ZERO);
 
             let new_pos = position - body_trail.last_anchor_position;
@@ -46,6 +71,59 @@ pub mod systems {
         }
     }
 
+    // rebuilds trails from checkpoints preceding the new time in the playback direction
+    pub fn rebuild_trails_on_seek(
+        mut ev: EventReader<SpaceSimulationSeeked>,
+        mut body_trails: Query<&mut BodyTrail>,
+        history: Res<SpaceSimulationHistory>,
+        simulation: Res<SpaceSimulation>,
+        params: Res<SpaceSimulationParams>,
+    ) {
+        use ringbuffer::{RingBufferExt, RingBufferWrite};
+
+        let Some(seeked) = ev.iter().last() else { return };
+
+        let checkpoints: Box<dyn Iterator<Item = _>> = if params.speed < 0.0 {
+            Box::new(
+                history
+                    .checkpoints()
+                    .rev()
+                    .filter(|(time, _)| **time > seeked.time)
+                    .map(|(_, bodies)| bodies),
+            )
+        } else {
+            Box::new(
+                history
+                    .checkpoints()
+                    .filter(|(time, _)| **time < seeked.time)
+                    .map(|(_, bodies)| bodies),
+            )
+        };
+
+        for mut body_trail in &mut body_trails {
+            body_trail.trail.clear();
+        }
+
+        for bodies in checkpoints.chain(std::iter::once(&simulation.bodies)) {
+            for mut body_trail in &mut body_trails {
+                let Some(index) = bodies.index(body_trail.body) else { continue };
+
+                let anchor_position = match body_trail.anchor {
+                    Some(anchor) => {
+                        let Some(anchor) = bodies.index(anchor) else { continue };
+                        bodies.positions()[anchor]
+                    }
+                    None => DVec3::ZERO,
+                };
+
+                body_trail.last_anchor_position = anchor_position;
+                body_trail
+                    .trail
+                    .push(bodies.positions()[index] - anchor_position);
+            }
+        }
+    }
+
     pub fn sync_polylines_to_trails(
         mut polylines: ResMut<Assets<Polyline>>,
         mut trails: Query<(&mut Transform, &BodyTrail, &Handle<Polyline>)>,
@@ -95,13 +173,13 @@ pub mod systems {
         };
 
         let BodyRef(body) = bodies.get(focused[Primary]).unwrap();
-        let anchor = bodies.get(focused[Secondary]).ok().map(|BodyRef(b)| b);
+        let anchor = bodies.get(focused[Secondary]).ok().map(|BodyRef(b)| *b);
 
         for mut body_trail in &mut body_trails {
-            if body_trail.body_name == *body {
+            if body_trail.body == *body {
                 use ringbuffer::RingBufferExt;
 
-                body_trail.anchor = anchor.cloned();
+                body_trail.anchor = anchor;
                 body_trail.trail.clear();
                 return;
             }
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+#[derive(Resource, Default)]
+pub struct ShowLagrangePoints {
+    pub value: bool,
+}
+
+// marker of the point L1 - L5 by its index
+#[derive(Component)]
+pub struct LagrangePointMarker(pub usize);
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_ecs_markers::params::Marker;
+
+    use crate::space::{
+        display::{custom_params::ComputedScale, BodyRef},
+        scene::markers::{FocusedBody, MainCamera3d},
+        simulation::SpaceSimulation,
+    };
+
+    use super::{LagrangePointMarker, ShowLagrangePoints};
+
+    pub fn display_lagrange_points(
+        camera: Query<(&GlobalTransform, &Camera), With<MainCamera3d>>,
+        focused: Marker<FocusedBody>,
+        bodies: Query<(&GlobalTransform, &BodyRef)>,
+        mut markers: Query<(&mut Transform, &mut Visibility, &LagrangePointMarker)>,
+        show: Res<ShowLagrangePoints>,
+        simulation: Res<SpaceSimulation>,
+        scale: ComputedScale,
+    ) {
+        use FocusedBody::*;
+
+        let scale = scale.get_scale();
+
+        let lagrange = bodies
+            .get(focused[Primary])
+            .ok()
+            .zip(bodies.get(focused[Secondary]).ok())
+            .filter(|_| show.value)
+            .and_then(|((transform, BodyRef(primary)), (_, BodyRef(secondary)))| {
+                let i = simulation.bodies.index(*primary)?;
+                let j = simulation.bodies.index(*secondary)?;
+
+                let points = simulation.bodies.lagrange_points(i, j)?;
+
+                Some((transform, simulation.bodies.positions()[i], points))
+            });
+
+        for (mut marker_transform, mut visibility, LagrangePointMarker(k)) in &mut markers {
+            visibility.is_visible = false;
+
+            let Some((body_transform, body_position, points)) = &lagrange else { continue };
+
+            let (camera_transform, camera) = camera.single();
+
+            let Some(viewport) = camera.logical_viewport_size() else { return };
+
+            // points are placed relative to the primary body, as it is already in the world
+            let translation = body_transform.translation()
+                + ((points.positions[*k] - *body_position) * scale).as_vec3();
+
+            let projected = camera
+                .world_to_viewport(camera_transform, translation)
+                .map(|x| x - viewport / 2.0);
+
+            let Some(projected) = projected else { continue };
+
+            marker_transform.translation.x = projected.x;
+            marker_transform.translation.y = projected.y;
+
+            visibility.is_visible = true;
+        }
+    }
+}
@@ -9,6 +9,15 @@ pub use camera::*;
 pub mod sync;
 pub use sync::*;
 
+pub mod lagrange_points;
+pub use lagrange_points::*;
+
+pub mod placement;
+pub use placement::*;
+
+pub mod prediction;
+pub use prediction::*;
+
 pub mod realistic;
 pub use realistic::*;
 
@@ -118,11 +127,15 @@ impl Plugin for DisplayPlugin {
             use body_trail::systems::*;
 
             app.add_system_to_stage(DisplayStage::Sync, extract_positions_from_simulations);
+            app.add_system_to_stage(
+                DisplayStage::Sync,
+                rebuild_trails_on_seek.after(extract_positions_from_simulations),
+            );
 
             app.add_system_to_stage(
                 DisplayStage::Sync,
                 sync_polylines_to_trails
-                    .after(extract_positions_from_simulations)
+                    .after(rebuild_trails_on_seek)
                     .after(DisplayStageSyncSystems::PreDisplayLogic),
             );
         }
@@ -169,5 +182,35 @@ impl Plugin for DisplayPlugin {
                 display_selection_rects.after(DisplayStageSyncSystems::TransformPropagate),
             );
         }
+
+        'add_placement_arrow_sync: {
+            use placement::systems::*;
+
+            app.add_system_to_stage(
+                DisplayStage::Sync,
+                display_placement_arrow.after(DisplayStageSyncSystems::PreDisplayLogic),
+            );
+        }
+
+        'add_prediction_sync: {
+            use crate::space::simulation::prediction::systems::update_prediction;
+            use prediction::systems::*;
+
+            app.add_system(select_predicted_bodies.before(update_prediction));
+            app.add_system_to_stage(
+                DisplayStage::Sync,
+                sync_prediction_dashes.after(DisplayStageSyncSystems::PreDisplayLogic),
+            );
+        }
+
+        'add_lagrange_points_sync: {
+            use lagrange_points::systems::*;
+
+            app.init_resource::<ShowLagrangePoints>();
+            app.add_system_to_stage(
+                DisplayStage::Sync,
+                display_lagrange_points.after(DisplayStageSyncSystems::TransformPropagate),
+            );
+        }
     }
 }
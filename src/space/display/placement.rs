@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+// velocity arrow of the body being placed
+#[derive(Component)]
+pub struct PlacementArrow;
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_polyline::prelude::Polyline;
+
+    use super::PlacementArrow;
+    use crate::space::{
+        display::{custom_params::ComputedScale, RelativeWorldOffset},
+        scene::BodyPlacement,
+        simulation::SpaceSimulation,
+    };
+
+    pub fn display_placement_arrow(
+        mut polylines: ResMut<Assets<Polyline>>,
+        mut arrows: Query<(&Handle<Polyline>, &mut Visibility), With<PlacementArrow>>,
+        placement: Res<BodyPlacement>,
+        simulation: Res<SpaceSimulation>,
+        relative_world_offset: Res<RelativeWorldOffset>,
+        scale: ComputedScale,
+    ) {
+        let scale = scale.get_scale();
+        let offset = relative_world_offset.translation;
+
+        let ends = placement.drag.as_ref().and_then(|drag| {
+            let reference = simulation.bodies.index(drag.reference)?;
+            let position = simulation.bodies.positions()[reference];
+
+            Some((
+                drag.normal,
+                ((position + drag.start - offset) * scale).as_vec3(),
+                ((position + drag.end - offset) * scale).as_vec3(),
+            ))
+        });
+
+        for (polyline_handle, mut visibility) in &mut arrows {
+            visibility.is_visible = false;
+
+            let Some((normal, start, end)) = ends else { continue };
+
+            let polyline = polylines.get_mut(polyline_handle).unwrap();
+
+            // head is drawn in the plane of placement
+            let back = (start - end) * 0.15;
+            let side = normal.as_vec3().cross(back);
+
+            polyline.vertices = vec![
+                start,
+                end,
+                end + back + side * 0.5,
+                end,
+                end + back - side * 0.5,
+            ];
+
+            visibility.is_visible = start != end;
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+
+use crate::space::simulation::BodyId;
+
+// predicted trajectory is split into dashes, as polylines can not have gaps
+pub const PREDICTION_DASHES: usize = 64;
+
+#[derive(Component)]
+pub struct PredictionDash {
+    pub body: BodyId,
+    pub dash: usize,
+}
+
+pub mod systems {
+    use bevy::{math::DVec3, prelude::*, utils::HashMap};
+    use bevy_ecs_markers::params::Marker;
+    use bevy_polyline::prelude::{Polyline, PolylineBundle, PolylineMaterial};
+
+    use super::{PredictionDash, PREDICTION_DASHES};
+    use crate::space::{
+        display::{custom_params::ComputedScale, BodyRef, BodyTrail, RelativeWorldOffset},
+        scene::markers::{BodySystemRoot, FocusedBody},
+        simulation::{BodyId, SpaceSimulation, SpaceSimulationPrediction},
+    };
+
+    // focused body, or every body that has a trail
+    fn shown_bodies(
+        prediction: &SpaceSimulationPrediction,
+        simulation: &SpaceSimulation,
+        focused: Option<BodyId>,
+    ) -> Vec<BodyId> {
+        if !prediction.enabled {
+            return vec![];
+        }
+
+        if !prediction.all_bodies {
+            return focused.into_iter().collect();
+        }
+
+        simulation
+            .bodies
+            .ids()
+            .iter()
+            .zip(simulation.bodies.masses())
+            .filter(|(_, mass)| **mass > 0.0)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub fn select_predicted_bodies(
+        mut prediction: ResMut<SpaceSimulationPrediction>,
+        simulation: Res<SpaceSimulation>,
+        focused_body: Marker<FocusedBody>,
+        bodies: Query<&BodyRef>,
+        trails: Query<&BodyTrail>,
+    ) {
+        use FocusedBody::*;
+
+        let focused = bodies.get(focused_body[Primary]).ok().map(|BodyRef(b)| *b);
+
+        let mut recorded = shown_bodies(&prediction, &simulation, focused);
+
+        // trajectories are drawn relative to the anchors of the trails
+        let anchors = trails
+            .iter()
+            .filter(|trail| recorded.contains(&trail.body))
+            .filter_map(|trail| trail.anchor)
+            .collect::<Vec<_>>();
+
+        for anchor in anchors {
+            if !recorded.contains(&anchor) {
+                recorded.push(anchor);
+            }
+        }
+
+        if prediction.bodies != recorded {
+            prediction.bodies = recorded;
+            prediction.invalidate();
+        }
+    }
+
+    pub fn sync_prediction_dashes(
+        mut commands: Commands,
+        mut polylines: ResMut<Assets<Polyline>>,
+        mut polyline_materials: ResMut<Assets<PolylineMaterial>>,
+        mut dashes: Query<(Entity, &PredictionDash, &Handle<Polyline>, &mut Visibility)>,
+        prediction: Res<SpaceSimulationPrediction>,
+        simulation: Res<SpaceSimulation>,
+        focused_body: Marker<FocusedBody>,
+        bodies: Query<&BodyRef>,
+        trails: Query<&BodyTrail>,
+        body_system_root: Query<Entity, With<BodySystemRoot>>,
+        relative_world_offset: Res<RelativeWorldOffset>,
+        scale: ComputedScale,
+        mut material: Local<Option<Handle<PolylineMaterial>>>,
+    ) {
+        use FocusedBody::*;
+
+        let scale = scale.get_scale();
+        let offset = relative_world_offset.translation;
+
+        let focused = bodies.get(focused_body[Primary]).ok().map(|BodyRef(b)| *b);
+        let shown = shown_bodies(&prediction, &simulation, focused);
+
+        let mut spawned = vec![];
+
+        for (entity, dash, ..) in &dashes {
+            if shown.contains(&dash.body) {
+                spawned.push(dash.body);
+            } else {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let material = material
+            .get_or_insert_with(|| {
+                polyline_materials.add(PolylineMaterial {
+                    width: 2.0,
+                    color: Color::rgba(1.0, 0.85, 0.4, 0.6),
+                    ..Default::default()
+                })
+            })
+            .clone();
+
+        let missing = shown
+            .iter()
+            .filter(|body| !spawned.contains(body))
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            commands
+                .entity(body_system_root.single())
+                .with_children(|commands| {
+                    for body in missing {
+                        for dash in 0..PREDICTION_DASHES {
+                            commands.spawn((
+                                PolylineBundle {
+                                    polyline: polylines.add(Polyline {
+                                        vertices: Vec::with_capacity(16),
+                                    }),
+                                    material: material.clone(),
+                                    ..Default::default()
+                                },
+                                PredictionDash { body: *body, dash },
+                            ));
+                        }
+                    }
+                });
+        }
+
+        let Some(trajectories) = &prediction.trajectories else {
+            for (.., mut visibility) in &mut dashes {
+                visibility.is_visible = false;
+            }
+            return;
+        };
+
+        // samples already passed in the playback direction are not drawn
+        let direction = match trajectories.times.as_slice() {
+            [first, second, ..] => (*second - *first).num_milliseconds().signum(),
+            _ => 0,
+        };
+        let first_ahead = trajectories
+            .times
+            .iter()
+            .position(|time| (*time - simulation.time).num_milliseconds() * direction > 0)
+            .unwrap_or(trajectories.times.len());
+
+        // path ahead of the body, starting at its current position
+        let path_of = |body: BodyId| -> Vec<Vec3> {
+            let Some(index) = simulation.bodies.index(body) else { return vec![] };
+            let Some(positions) = trajectories.positions.get(&body) else { return vec![] };
+
+            let anchor = trails
+                .iter()
+                .find(|trail| trail.body == body)
+                .and_then(|trail| trail.anchor)
+                .and_then(|anchor| {
+                    let index = simulation.bodies.index(anchor)?;
+                    let positions = trajectories.positions.get(&anchor)?;
+
+                    Some((simulation.bodies.positions()[index], positions))
+                });
+
+            let ahead =
+                positions
+                    .iter()
+                    .enumerate()
+                    .skip(first_ahead)
+                    .map_while(|(k, position)| match anchor {
+                        Some((anchor_now, anchor_positions)) => {
+                            Some(*position - *anchor_positions.get(k)? + anchor_now)
+                        }
+                        None => Some(*position),
+                    });
+
+            std::iter::once(simulation.bodies.positions()[index])
+                .chain(ahead)
+                .map(|position: DVec3| ((position - offset) * scale).as_vec3())
+                .collect()
+        };
+
+        let mut paths: HashMap<BodyId, Vec<Vec3>> = default();
+
+        for (_, dash, polyline_handle, mut visibility) in &mut dashes {
+            visibility.is_visible = false;
+
+            let path = paths.entry(dash.body).or_insert_with(|| path_of(dash.body));
+
+            // each dash covers the first half of its share of the path
+            let share = path.len() as f64 / PREDICTION_DASHES as f64;
+            let start = (dash.dash as f64 * share) as usize;
+            let end = ((dash.dash as f64 + 0.5) * share) as usize + 1;
+
+            if end > path.len() || end - start < 2 {
+                continue;
+            }
+
+            let polyline = polylines.get_mut(polyline_handle).unwrap();
+            polyline.vertices.clear();
+            polyline.vertices.extend_from_slice(&path[start..end]);
+
+            visibility.is_visible = true;
+        }
+    }
+}
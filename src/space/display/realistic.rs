@@ -39,7 +39,7 @@ pub mod systems {
         for (mut transform, parent) in &mut meshes {
             let Ok(BodyRef(body_ref)) = bodies.get(parent.get()) else { continue };
 
-            let index = simulation.bodies.get_index(body_ref);
+            let Some(index) = simulation.bodies.index(*body_ref) else { continue };
 
             let body_rotation = &simulation.bodies.rotations()[index];
             transform.rotation = simulation.calculate_body_rotation(
@@ -48,20 +48,27 @@ pub mod systems {
                 body_rotation.sideral_rotation_speed,
             );
 
-            if *previous_scale != scale {
-                transform.scale = Vec3::splat((simulation.bodies.radiuses()[index] * scale) as f32);
+            // radius may be edited at any time, not only when the scale changes
+            let scaled = Vec3::splat((simulation.bodies.radiuses()[index] * scale) as f32);
+
+            if transform.scale != scaled {
+                transform.scale = scaled;
             }
         }
 
-        if *previous_scale != scale {
-            for (mut light, parent, &RelativeLightIntensivity(relative_intensity)) in &mut lights {
+        for (mut light, parent, &RelativeLightIntensivity(relative_intensity)) in &mut lights {
+            if *previous_scale != scale {
                 light.intensity = (relative_intensity * scale * scale) as f32;
+            }
+
+            let Ok(BodyRef(body_ref)) = bodies.get(parent.get()) else { continue };
 
-                let Ok(BodyRef(body_ref)) = bodies.get(parent.get()) else { continue };
+            let Some(index) = simulation.bodies.index(*body_ref) else { continue };
 
-                let index = simulation.bodies.get_index(body_ref);
+            let radius = (simulation.bodies.radiuses()[index] * scale) as f32;
 
-                light.radius = (simulation.bodies.radiuses()[index] * scale) as f32;
+            if light.radius != radius {
+                light.radius = radius;
             }
         }
 
@@ -24,7 +24,7 @@ pub mod systems {
 
         let Ok(BodyRef(body)) = bodies.get(focused_body[Primary]) else { return };
 
-        let index = simulation.bodies.get_index(body);
+        let Some(index) = simulation.bodies.index(*body) else { return };
 
         relative_world_offset.translation = simulation.bodies.positions()[index];
     }
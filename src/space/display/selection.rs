@@ -55,7 +55,7 @@ pub mod systems {
 
                 let (body_transform, BodyRef(body_ref)) = bodies.get(selected).unwrap();
 
-                let index = simulation.bodies.get_index(body_ref);
+                let Some(index) = simulation.bodies.index(*body_ref) else { continue };
 
                 let radius = simulation.bodies.radiuses()[index];
 
@@ -16,8 +16,8 @@ pub mod systems {
         let scale = scale.get_scale();
         let offset = relative_world_offset.translation;
 
-        for (mut transform, BodyRef(i)) in &mut bodies {
-            let index = simulation.bodies.get_index(i);
+        for (mut transform, BodyRef(body)) in &mut bodies {
+            let Some(index) = simulation.bodies.index(*body) else { continue };
 
             let position = simulation.bodies.positions()[index];
 
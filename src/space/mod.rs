@@ -1,5 +1,6 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
+pub mod cli;
 pub mod controls;
 pub mod display;
 pub mod ext;
@@ -1,4 +1,7 @@
-use crate::space::{display::StarMaterial, simulation::SpaceBodyRotation};
+use crate::space::{
+    display::StarMaterial,
+    simulation::{SpaceBodyNonGravitational, SpaceBodyRotation, SpaceBodyZonalHarmonics},
+};
 use bevy::{math::DVec3, prelude::*, tasks::Task};
 use bevy_debug_text_overlay::screen_print;
 use chrono::{DateTime, Duration, Utc};
@@ -27,17 +30,9 @@ pub struct BodyParams {
     sideralRotation: f64,
 }
 
-pub async fn get_body_dynamics_using_nasa_horizons(
-    date: DateTime<Utc>,
-    name: impl ToString,
-) -> anyhow::Result<SpaceBody> {
-    use anyhow::anyhow;
-    let _guard = LIMITER.acquire().await;
-
-    let data_regex = regex::Regex::new(r"\$\$SOE(.*)\$\$EOE").unwrap();
-    let coord_regex = regex::Regex::new(r"[XYZ] ?=([ \-+0-9E.]+)").unwrap();
-    let name_regex = regex::Regex::new(r"Target body name *: *([a-zA-Z0-9]+)").unwrap();
+const MAX_RETRIES: usize = 32;
 
+async fn request_nasa_horizons_vectors(date: DateTime<Utc>, name: &str) -> anyhow::Result<String> {
     #[allow(non_snake_case)]
     #[derive(serde::Serialize)]
     struct RequestQuery {
@@ -50,8 +45,6 @@ pub async fn get_body_dynamics_using_nasa_horizons(
         QUANTITIES: String,
     }
 
-    const MAX_RETRIES: usize = 32;
-
     let body_dynamics = async {
         for _ in 0..MAX_RETRIES {
             let mut resp = surf::Client::new()
@@ -66,7 +59,7 @@ pub async fn get_body_dynamics_using_nasa_horizons(
                         .unwrap()
                         .format("%Y-%b-%d-%T")
                         .to_string(),
-                    OBJ_DATA: "NO".into(),
+                    OBJ_DATA: "YES".into(),
                     QUANTITIES: "1".into(),
                 })
                 .ok()?
@@ -95,8 +88,18 @@ pub async fn get_body_dynamics_using_nasa_horizons(
         return Err(anyhow::anyhow!("Failed to get NASA body: Serive Unavailable"));
     };
 
+    Ok(result_dynamics)
+}
+
+// position and velocity in the simulation frame
+fn parse_nasa_horizons_vectors(result_dynamics: &str) -> anyhow::Result<(DVec3, DVec3)> {
+    use anyhow::anyhow;
+
+    let data_regex = regex::Regex::new(r"\$\$SOE(.*)\$\$EOE").unwrap();
+    let coord_regex = regex::Regex::new(r"[XYZ] ?=([ \-+0-9E.]+)").unwrap();
+
     let parsed = data_regex
-        .captures(&result_dynamics)
+        .captures(result_dynamics)
         .ok_or_else(|| anyhow!("Failed to regex NASA dynamics: [{result_dynamics:?}]"))?
         .get(0)
         .ok_or_else(|| anyhow!("Regex group [0] of NASA dynamics failed"))?
@@ -145,6 +148,49 @@ pub async fn get_body_dynamics_using_nasa_horizons(
 
     let velocity = DVec3::new(x, y, z) * 1000.0;
 
+    Ok((position, velocity))
+}
+
+// position and velocity only, without the physical parameters of the body
+pub async fn get_body_state_using_nasa_horizons(
+    date: DateTime<Utc>,
+    name: impl ToString,
+) -> anyhow::Result<(DVec3, DVec3)> {
+    let _guard = LIMITER.acquire().await;
+
+    parse_nasa_horizons_vectors(&request_nasa_horizons_vectors(date, &name.to_string()).await?)
+}
+
+pub async fn get_body_dynamics_using_nasa_horizons(
+    date: DateTime<Utc>,
+    name: impl ToString,
+) -> anyhow::Result<SpaceBody> {
+    use anyhow::anyhow;
+    let _guard = LIMITER.acquire().await;
+
+    let name_regex = regex::Regex::new(r"Target body name *: *([a-zA-Z0-9]+)").unwrap();
+    let non_gravitational_regex =
+        regex::Regex::new(r"\b(A1|A2|A3|AMRAT) *= *([\-+0-9E.]+)").unwrap();
+
+    let result_dynamics = request_nasa_horizons_vectors(date, &name.to_string()).await?;
+
+    let (position, velocity) = parse_nasa_horizons_vectors(&result_dynamics)?;
+
+    // comets and some asteroids have non gravitational parameters in object data
+    let mut non_gravitational: Option<SpaceBodyNonGravitational> = None;
+
+    for captures in non_gravitational_regex.captures_iter(&result_dynamics) {
+        let Ok(value) = captures[2].parse::<f64>() else { continue };
+        let parameters = non_gravitational.get_or_insert_with(default);
+
+        match &captures[1] {
+            "A1" => parameters.a1 = value,
+            "A2" => parameters.a2 = value,
+            "A3" => parameters.a3 = value,
+            _ => parameters.area_to_mass = value,
+        }
+    }
+
     let name = name_regex
         .captures(&result_dynamics)
         .ok_or_else(|| anyhow!("name_regex fault"))?
@@ -190,12 +236,16 @@ pub async fn get_body_dynamics_using_nasa_horizons(
         mass: data
             .mass
             .map(|mass| mass.massValue * 10f64.powf(mass.massExponent))
-            .unwrap_or(1.0),
+            // unknown mass, body is simulated as a test particle
+            .unwrap_or(0.0),
         rotation: SpaceBodyRotation {
             initial: Default::default(),
             sideral_rotation_offset: Default::default(),
             sideral_rotation_speed: data.sideralRotation,
         },
+        zonal_harmonics: None,
+        non_gravitational,
+        spacecraft: None,
     })
 }
 
@@ -207,152 +257,7 @@ impl Plugin for NasaHorizonsPlugin {
         app.add_event::<NasaBodyAddition>();
 
         app.init_resource::<NasaTasksManager>();
-        app.insert_resource({
-            use SpaceBodyKnownDetailsMaterial::*;
-
-            let mut map = bevy::utils::HashMap::new();
-
-            // SUN
-            map.insert(
-                "10".into(),
-                SpaceBodyKnownDetails {
-                    mass: 1988500e24,
-                    material: Star(StarMaterial {
-                        primary_color: Color::rgb(8.0 * 4.0, 8.0 * 4.0, 0.0),
-                        secondary_color: Color::rgb(8.0 * 4.0, 5.2 * 4.0, 0.0),
-                        ..Default::default()
-                    }),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: Default::default(),
-                },
-            );
-
-            // MERCURY
-            map.insert(
-                "199".into(),
-                SpaceBodyKnownDetails {
-                    mass: 3.302e23,
-                    material: TexturePath("textures/mercury_base_color.jpg".into()),
-                    rotation: Quat::from_euler(
-                        EulerRot::XYZ,
-                        28.55f32.to_radians(),
-                        329.548f32.to_radians(),
-                        0.0,
-                    ),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.00000124001,
-                },
-            );
-
-            // VENUS
-            map.insert(
-                "299".into(),
-                SpaceBodyKnownDetails {
-                    mass: 48.685e23,
-                    material: TexturePath("textures/venus_base_color.jpg".into()),
-                    rotation: Quat::from_euler(
-                        EulerRot::XYZ,
-                        157.16f32.to_radians(),
-                        19.8f32.to_radians(),
-                        0.0,
-                    ),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: -0.00000029924,
-                },
-            );
-
-            // EARTH
-            map.insert(
-                "399".into(),
-                SpaceBodyKnownDetails {
-                    mass: 5.97219e24,
-                    material: TexturePath("textures/earth_base_color.jpg".into()),
-                    rotation: Quat::from_euler(
-                        EulerRot::XYZ,
-                        -23.4392911f32.to_radians(),
-                        (360.0 - 280.147f32).to_radians(),
-                        0.0,
-                    ),
-                    sideral_rotation_offset: -15445678.5462,
-                    sideral_rotation_speed: 0.00007292115,
-                },
-            );
-
-            // MARS
-            map.insert(
-                "499".into(),
-                SpaceBodyKnownDetails {
-                    mass: 6.4171e23,
-                    material: TexturePath("textures/mars_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.0000708822,
-                },
-            );
-
-            // JUPITER
-            map.insert(
-                "599".into(),
-                SpaceBodyKnownDetails {
-                    mass: 189818.722e22,
-                    material: TexturePath("textures/jupiter_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.00007292115,
-                },
-            );
-
-            // SATURN
-            map.insert(
-                "699".into(),
-                SpaceBodyKnownDetails {
-                    mass: 5.6834e26,
-                    material: TexturePath("textures/saturn_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.0334979 / (24.0 * 60.0 * 60.0),
-                },
-            );
-
-            // URANUS
-            map.insert(
-                "799".into(),
-                SpaceBodyKnownDetails {
-                    mass: 86.813e24,
-                    material: TexturePath("textures/uranus_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: -0.000101237,
-                },
-            );
-
-            // NEPTUNE
-            map.insert(
-                "899".into(),
-                SpaceBodyKnownDetails {
-                    mass: 102.409e24,
-                    material: TexturePath("textures/neptune_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.000108338,
-                },
-            );
-
-            // MOON
-            map.insert(
-                "301".into(),
-                SpaceBodyKnownDetails {
-                    mass: 7.349e22,
-                    material: TexturePath("textures/moon_base_color.jpg".into()),
-                    rotation: Default::default(),
-                    sideral_rotation_offset: Default::default(),
-                    sideral_rotation_speed: 0.0000026617,
-                },
-            );
-
-            SpaceBodiesKnownDetails { map }
-        });
+        app.init_resource::<SpaceBodiesKnownDetails>();
 
         app.add_system(systems::reqeust_nasa_bodies_on_event);
         app.add_system(
@@ -402,6 +307,7 @@ pub struct SpaceBodyKnownDetails {
     pub rotation: Quat,
     pub sideral_rotation_offset: f64,
     pub sideral_rotation_speed: f64,
+    pub zonal_harmonics: Option<SpaceBodyZonalHarmonics>,
 }
 
 #[derive(Resource)]
@@ -409,6 +315,253 @@ pub struct SpaceBodiesKnownDetails {
     pub map: bevy::utils::HashMap<String, SpaceBodyKnownDetails>,
 }
 
+// pole is given in ICRF (degrees) and converted to the frame of horizons vectors as they are parsed
+fn pole_from_right_ascension_declination(right_ascension: f64, declination: f64) -> DVec3 {
+    const OBLIQUITY: f64 = 23.439281;
+
+    let (ra, dec, e) = (
+        right_ascension.to_radians(),
+        declination.to_radians(),
+        OBLIQUITY.to_radians(),
+    );
+
+    let equatorial = DVec3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+    let ecliptic = DVec3::new(
+        equatorial.x,
+        equatorial.y * e.cos() + equatorial.z * e.sin(),
+        -equatorial.y * e.sin() + equatorial.z * e.cos(),
+    );
+
+    DVec3::new(ecliptic.x, -ecliptic.z, -ecliptic.y)
+}
+
+impl SpaceBodiesKnownDetails {
+    pub fn fill_zonal_harmonics(&self, name: &str, body: &mut SpaceBody) {
+        if body.zonal_harmonics.is_none() {
+            body.zonal_harmonics = self
+                .map
+                .get(name)
+                .and_then(|details| details.zonal_harmonics);
+        }
+    }
+}
+
+impl Default for SpaceBodiesKnownDetails {
+    fn default() -> Self {
+        use SpaceBodyKnownDetailsMaterial::*;
+
+        let mut map = bevy::utils::HashMap::new();
+
+        // SUN
+        map.insert(
+            "10".into(),
+            SpaceBodyKnownDetails {
+                mass: 1988500e24,
+                material: Star(StarMaterial {
+                    primary_color: Color::rgb(8.0 * 4.0, 8.0 * 4.0, 0.0),
+                    secondary_color: Color::rgb(8.0 * 4.0, 5.2 * 4.0, 0.0),
+                    ..Default::default()
+                }),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: Default::default(),
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 2.2e-7,
+                    reference_radius: 695_700_000.0,
+                    pole: pole_from_right_ascension_declination(286.13, 63.87),
+                    ..default()
+                }),
+            },
+        );
+
+        // MERCURY
+        map.insert(
+            "199".into(),
+            SpaceBodyKnownDetails {
+                mass: 3.302e23,
+                material: TexturePath("textures/mercury_base_color.jpg".into()),
+                rotation: Quat::from_euler(
+                    EulerRot::XYZ,
+                    28.55f32.to_radians(),
+                    329.548f32.to_radians(),
+                    0.0,
+                ),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.00000124001,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 5.03e-5,
+                    reference_radius: 2_440_530.0,
+                    pole: pole_from_right_ascension_declination(281.0103, 61.4155),
+                    ..default()
+                }),
+            },
+        );
+
+        // VENUS
+        map.insert(
+            "299".into(),
+            SpaceBodyKnownDetails {
+                mass: 48.685e23,
+                material: TexturePath("textures/venus_base_color.jpg".into()),
+                rotation: Quat::from_euler(
+                    EulerRot::XYZ,
+                    157.16f32.to_radians(),
+                    19.8f32.to_radians(),
+                    0.0,
+                ),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: -0.00000029924,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 4.458e-6,
+                    reference_radius: 6_051_800.0,
+                    pole: pole_from_right_ascension_declination(272.76, 67.16),
+                    ..default()
+                }),
+            },
+        );
+
+        // EARTH
+        map.insert(
+            "399".into(),
+            SpaceBodyKnownDetails {
+                mass: 5.97219e24,
+                material: TexturePath("textures/earth_base_color.jpg".into()),
+                rotation: Quat::from_euler(
+                    EulerRot::XYZ,
+                    -23.4392911f32.to_radians(),
+                    (360.0 - 280.147f32).to_radians(),
+                    0.0,
+                ),
+                sideral_rotation_offset: -15445678.5462,
+                sideral_rotation_speed: 0.00007292115,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 1.08262668e-3,
+                    j3: -2.532656e-6,
+                    j4: -1.6196e-6,
+                    reference_radius: 6_378_137.0,
+                    pole: pole_from_right_ascension_declination(0.0, 90.0),
+                }),
+            },
+        );
+
+        // MARS
+        map.insert(
+            "499".into(),
+            SpaceBodyKnownDetails {
+                mass: 6.4171e23,
+                material: TexturePath("textures/mars_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.0000708822,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 1.96045e-3,
+                    j3: 3.1450e-5,
+                    j4: -1.5377e-5,
+                    reference_radius: 3_396_190.0,
+                    pole: pole_from_right_ascension_declination(317.269, 54.432),
+                }),
+            },
+        );
+
+        // JUPITER
+        map.insert(
+            "599".into(),
+            SpaceBodyKnownDetails {
+                mass: 189818.722e22,
+                material: TexturePath("textures/jupiter_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.00007292115,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 1.46965e-2,
+                    j3: -4.2e-8,
+                    j4: -5.8661e-4,
+                    reference_radius: 71_492_000.0,
+                    pole: pole_from_right_ascension_declination(268.057, 64.495),
+                }),
+            },
+        );
+
+        // SATURN
+        map.insert(
+            "699".into(),
+            SpaceBodyKnownDetails {
+                mass: 5.6834e26,
+                material: TexturePath("textures/saturn_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.0334979 / (24.0 * 60.0 * 60.0),
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 1.62907e-2,
+                    j3: 5.9e-8,
+                    j4: -9.358e-4,
+                    reference_radius: 60_330_000.0,
+                    pole: pole_from_right_ascension_declination(40.589, 83.537),
+                }),
+            },
+        );
+
+        // URANUS
+        map.insert(
+            "799".into(),
+            SpaceBodyKnownDetails {
+                mass: 86.813e24,
+                material: TexturePath("textures/uranus_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: -0.000101237,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 3.5107e-3,
+                    j4: -3.42e-5,
+                    reference_radius: 25_559_000.0,
+                    pole: pole_from_right_ascension_declination(257.311, -15.175),
+                    ..default()
+                }),
+            },
+        );
+
+        // NEPTUNE
+        map.insert(
+            "899".into(),
+            SpaceBodyKnownDetails {
+                mass: 102.409e24,
+                material: TexturePath("textures/neptune_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.000108338,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 3.4084e-3,
+                    j4: -3.34e-5,
+                    reference_radius: 24_764_000.0,
+                    pole: pole_from_right_ascension_declination(299.36, 43.46),
+                    ..default()
+                }),
+            },
+        );
+
+        // MOON
+        map.insert(
+            "301".into(),
+            SpaceBodyKnownDetails {
+                mass: 7.349e22,
+                material: TexturePath("textures/moon_base_color.jpg".into()),
+                rotation: Default::default(),
+                sideral_rotation_offset: Default::default(),
+                sideral_rotation_speed: 0.0000026617,
+                zonal_harmonics: Some(SpaceBodyZonalHarmonics {
+                    j2: 2.0321e-4,
+                    j3: 8.476e-6,
+                    j4: -9.592e-6,
+                    reference_radius: 1_738_000.0,
+                    pole: pole_from_right_ascension_declination(269.9949, 66.5392),
+                }),
+            },
+        );
+
+        SpaceBodiesKnownDetails { map }
+    }
+}
+
 pub mod systems {
     use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
 
@@ -459,6 +612,7 @@ pub mod systems {
             let sideral_rotation_speed;
             let sideral_rotation_offset;
             let material;
+            let zonal_harmonics;
 
             if let Some(details) = known_details.map.get(&response.name) {
                 mass = details.mass;
@@ -466,6 +620,7 @@ pub mod systems {
                 sideral_rotation_speed = details.sideral_rotation_speed;
                 sideral_rotation_offset = details.sideral_rotation_offset;
                 material = details.material.clone();
+                zonal_harmonics = details.zonal_harmonics;
             } else {
                 mass = response.body.mass;
                 rotation = Default::default();
@@ -474,6 +629,7 @@ pub mod systems {
                 material = crate::space::nasa_horizons::SpaceBodyKnownDetailsMaterial::TexturePath(
                     "textures/asteroid.jpg".into(),
                 );
+                zonal_harmonics = None;
             }
 
             let st = NasaBodyAddition {
@@ -486,6 +642,7 @@ pub mod systems {
                         sideral_rotation_offset,
                         sideral_rotation_speed,
                     },
+                    zonal_harmonics,
                     ..response.body
                 },
                 material,
@@ -500,9 +657,15 @@ pub mod systems {
     pub fn insert_nasa_bodies(
         mut ev: EventReader<NasaBodyAddition>,
         mut simulation: ResMut<SpaceSimulation>,
+        known_details: Res<SpaceBodiesKnownDetails>,
     ) {
-        for e in ev.iter().cloned() {
-            simulation.bodies.insert(e.name, e.body);
+        for mut e in ev.iter().cloned() {
+            // bodies saved before the coefficients were known
+            known_details.fill_zonal_harmonics(&e.name, &mut e.body);
+
+            if let Err(e) = simulation.bodies.insert(e.name, e.body) {
+                screen_print!(sec: 3.0, col: Color::RED, "{e}");
+            }
         }
     }
 }
@@ -0,0 +1,87 @@
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_ecs_markers::params::MarkerMut;
+
+    use crate::space::{
+        display::{custom_params::ComputedScale, BodyRef, BodyTrail, RealisticView},
+        ext::EntityOpsExt,
+        scene::markers::{FocusedBody, MainCamera3d, SelectedBody},
+        simulation::{BodiesCollided, BodyId, SpaceSimulation},
+    };
+
+    pub fn despawn_absorbed_bodies(
+        mut commands: Commands,
+        mut ev: EventReader<BodiesCollided>,
+        bodies: Query<(Entity, &BodyRef, &Children)>,
+        mut trails: Query<(Entity, &mut BodyTrail)>,
+        mut realistic_meshes: Query<&mut Transform, With<RealisticView>>,
+        mut focused: MarkerMut<FocusedBody>,
+        mut selected: MarkerMut<SelectedBody>,
+        camera: Query<Entity, (With<MainCamera3d>, With<Camera3d>)>,
+        simulation: Res<SpaceSimulation>,
+        scale: ComputedScale,
+    ) {
+        use FocusedBody::*;
+        use SelectedBody::*;
+
+        let scale = scale.get_scale();
+
+        let find_body = |id: BodyId| {
+            bodies
+                .iter()
+                .find(|(_, BodyRef(body), _)| *body == id)
+                .map(|(entity, _, children)| (entity, children))
+        };
+
+        for BodiesCollided {
+            survivor, absorbed, ..
+        } in ev.iter()
+        {
+            let (Some((survivor_entity, survivor_children)), Some((absorbed_entity, _))) =
+                (find_body(*survivor), find_body(*absorbed))
+            else {
+                continue;
+            };
+
+            if focused[Primary] == absorbed_entity {
+                focused[Primary] = survivor_entity;
+
+                commands.entity(camera.single()).set_parent(survivor_entity);
+            }
+            if focused[Secondary] == absorbed_entity {
+                focused[Secondary] = survivor_entity;
+            }
+
+            if selected[CurrentRedirected] == absorbed_entity
+                || selected[PreviousRedirected] == absorbed_entity
+            {
+                selected[Current].invalidate();
+                selected[Previous].invalidate();
+                selected[CurrentRedirected].invalidate();
+                selected[PreviousRedirected].invalidate();
+            }
+
+            for (trail_entity, mut trail) in &mut trails {
+                if trail.body == *absorbed {
+                    commands.entity(trail_entity).despawn_recursive();
+                } else if trail.anchor == Some(*absorbed) {
+                    use ringbuffer::RingBufferExt;
+
+                    trail.anchor = Some(*survivor);
+                    trail.trail.clear();
+                }
+            }
+
+            commands.entity(absorbed_entity).despawn_recursive();
+
+            let Some(index) = simulation.bodies.index(*survivor) else { continue };
+
+            for child in survivor_children.iter() {
+                if let Ok(mut transform) = realistic_meshes.get_mut(*child) {
+                    transform.scale =
+                        Vec3::splat((simulation.bodies.radiuses()[index] * scale) as f32);
+                }
+            }
+        }
+    }
+}
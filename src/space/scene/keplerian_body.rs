@@ -0,0 +1,46 @@
+use crate::space::simulation::KeplerianBody;
+
+pub struct SpawnKeplerianBodyRequest(pub KeplerianBody);
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_debug_text_overlay::screen_print;
+
+    use super::SpawnKeplerianBodyRequest;
+    use crate::space::{
+        nasa_horizons::{NasaBodyAddition, SpaceBodyKnownDetailsMaterial},
+        simulation::SpaceSimulation,
+    };
+
+    pub fn spawn_keplerian_body_on_request(
+        mut ev: EventReader<SpawnKeplerianBodyRequest>,
+        simulation: Res<SpaceSimulation>,
+        mut additions: EventWriter<NasaBodyAddition>,
+    ) {
+        for SpawnKeplerianBodyRequest(body) in ev.iter() {
+            if simulation.bodies.try_get_index(&body.name).is_some() {
+                screen_print!(sec: 3.0, col: Color::RED, "body [{}] already exists", body.name);
+                continue;
+            }
+
+            let Some(generated) = body.generate(&simulation.bodies, simulation.G, simulation.time)
+            else {
+                screen_print!(sec: 3.0, col: Color::RED, "can not place [{}]", body.name);
+                continue;
+            };
+
+            let parent = simulation.bodies.name(body.parent).unwrap();
+
+            screen_print!(sec: 3.0, col: Color::GREEN, "spawned [{}] around [{parent}]", body.name);
+
+            additions.send(NasaBodyAddition {
+                date: simulation.time,
+                name: body.name.clone(),
+                body: generated,
+                material: SpaceBodyKnownDetailsMaterial::TexturePath(
+                    "textures/asteroid.jpg".into(),
+                ),
+            });
+        }
+    }
+}
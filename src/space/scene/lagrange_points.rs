@@ -0,0 +1,64 @@
+use crate::space::simulation::BodyId;
+
+// test particle at the Lagrange point of the pair, `point` is 0 for L1 up to 4 for L5
+pub struct SpawnLagrangePointParticleRequest {
+    pub primary: BodyId,
+    pub secondary: BodyId,
+    pub point: usize,
+}
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_debug_text_overlay::screen_print;
+
+    use super::SpawnLagrangePointParticleRequest;
+    use crate::space::{
+        nasa_horizons::{NasaBodyAddition, SpaceBodyKnownDetailsMaterial},
+        simulation::{SpaceBody, SpaceSimulation},
+    };
+
+    pub fn spawn_lagrange_point_particle_on_request(
+        mut ev: EventReader<SpawnLagrangePointParticleRequest>,
+        simulation: Res<SpaceSimulation>,
+        mut additions: EventWriter<NasaBodyAddition>,
+        mut batch: Local<usize>,
+    ) {
+        for request in ev.iter() {
+            let (Some(i), Some(j)) = (
+                simulation.bodies.index(request.primary),
+                simulation.bodies.index(request.secondary),
+            ) else {
+                continue;
+            };
+
+            let (primary, secondary) =
+                (&simulation.bodies.names()[i], &simulation.bodies.names()[j]);
+
+            let Some(points) = simulation.bodies.lagrange_points(i, j) else {
+                screen_print!(sec: 3.0, col: Color::RED, "no Lagrange points of [{primary}] and [{secondary}]");
+                continue;
+            };
+
+            // names of particles must not clash with previously spawned ones
+            let name = format!("L{} {primary}-{secondary} #{}", request.point + 1, *batch);
+            *batch += 1;
+
+            screen_print!(sec: 3.0, col: Color::GREEN, "spawned [{name}]");
+
+            additions.send(NasaBodyAddition {
+                date: simulation.time,
+                name,
+                body: SpaceBody {
+                    position: points.positions[request.point],
+                    velocity: points.velocities[request.point],
+                    mass: 0.0,
+                    radius: 50_000.0,
+                    ..Default::default()
+                },
+                material: SpaceBodyKnownDetailsMaterial::TexturePath(
+                    "textures/asteroid.jpg".into(),
+                ),
+            });
+        }
+    }
+}
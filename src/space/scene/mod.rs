@@ -1,5 +1,19 @@
 pub use bevy::prelude::*;
 
+pub mod collision;
+
+pub mod keplerian_body;
+pub use keplerian_body::*;
+
+pub mod lagrange_points;
+pub use lagrange_points::*;
+
+pub mod placement;
+pub use placement::*;
+
+pub mod remove_body;
+pub use remove_body::*;
+
 pub mod selection;
 pub use selection::*;
 
@@ -9,6 +23,9 @@ pub use setup::*;
 pub mod spawn_body;
 pub use spawn_body::*;
 
+pub mod test_particles;
+pub use test_particles::*;
+
 pub mod markers {
     use bevy::prelude::*;
     use bevy_ecs_markers::EntityMarker;
@@ -53,6 +70,10 @@ impl Plugin for ScenePlugin {
 
         app.add_event::<DeselectionEvent>();
         app.add_event::<SelectionEvent>();
+        app.add_event::<SpawnTestParticlesRequest>();
+        app.add_event::<SpawnKeplerianBodyRequest>();
+        app.add_event::<SpawnLagrangePointParticleRequest>();
+        app.add_event::<RemoveBodyRequest>();
 
         {
             use setup::systems::*;
@@ -62,9 +83,49 @@ impl Plugin for ScenePlugin {
         }
 
         {
+            use crate::space::nasa_horizons::systems::insert_nasa_bodies;
             use spawn_body::systems::*;
 
-            app.add_system(spawn_nasa_body);
+            app.add_system(spawn_nasa_body.after(insert_nasa_bodies));
+        }
+
+        {
+            use test_particles::systems::*;
+
+            app.add_system(spawn_test_particles_on_request);
+        }
+
+        {
+            use placement::systems::*;
+
+            app.init_resource::<BodyPlacement>();
+            app.add_system(place_body_with_cursor);
+        }
+
+        {
+            use keplerian_body::systems::*;
+
+            app.add_system(spawn_keplerian_body_on_request);
+        }
+
+        {
+            use lagrange_points::systems::*;
+
+            app.add_system(spawn_lagrange_point_particle_on_request);
+        }
+
+        {
+            use crate::space::simulation::space_simulation::systems::simulation_take_step;
+            use collision::systems::*;
+
+            app.add_system(despawn_absorbed_bodies.after(simulation_take_step));
+        }
+
+        {
+            use crate::space::simulation::space_simulation::systems::simulation_take_step;
+            use remove_body::systems::*;
+
+            app.add_system(remove_body_on_request.after(simulation_take_step));
         }
 
         {
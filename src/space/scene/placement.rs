@@ -0,0 +1,172 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::space::simulation::BodyId;
+
+// arrow being drawn by the cursor, ends are relative to the reference body
+// so the arrow follows it while the simulation runs
+pub struct BodyPlacementDrag {
+    pub reference: BodyId,
+    pub normal: DVec3,
+    pub start: DVec3,
+    pub end: DVec3,
+}
+
+#[derive(Resource)]
+pub struct BodyPlacement {
+    pub active: bool,
+    pub name: String,
+    pub mass: f64,
+    pub radius: f64,
+    pub texture: String,
+    // simulated seconds the new body takes to cover the arrow relative to the reference body
+    pub arrow_time: f64,
+    pub drag: Option<BodyPlacementDrag>,
+}
+
+impl Default for BodyPlacement {
+    fn default() -> Self {
+        Self {
+            active: false,
+            name: "Новое тело".into(),
+            mass: 1e22,
+            radius: 1e6,
+            texture: "textures/asteroid.jpg".into(),
+            arrow_time: 86400.0,
+            drag: None,
+        }
+    }
+}
+
+impl BodyPlacement {
+    pub fn velocity(&self) -> Option<DVec3> {
+        let drag = self.drag.as_ref()?;
+
+        Some((drag.end - drag.start) / self.arrow_time)
+    }
+}
+
+pub mod systems {
+    use bevy::{math::DVec3, prelude::*};
+    use bevy_debug_text_overlay::screen_print;
+    use bevy_ecs_markers::params::Marker;
+    use bevy_egui::EguiContext;
+    use bevy_mod_raycast::RaycastSource;
+
+    use super::{BodyPlacement, BodyPlacementDrag};
+    use crate::space::{
+        display::{custom_params::ComputedScale, BodyRef, RelativeWorldOffset},
+        nasa_horizons::{NasaBodyAddition, SpaceBodyKnownDetailsMaterial},
+        scene::{markers::FocusedBody, SelectionRaycastSet},
+        simulation::{SpaceBody, SpaceSimulation},
+    };
+
+    pub fn place_body_with_cursor(
+        mut placement: ResMut<BodyPlacement>,
+        mut egui: ResMut<EguiContext>,
+        mouse: Res<Input<MouseButton>>,
+        camera: Query<&RaycastSource<SelectionRaycastSet>>,
+        bodies: Query<&BodyRef>,
+        focused_body: Marker<FocusedBody>,
+        simulation: Res<SpaceSimulation>,
+        relative_world_offset: Res<RelativeWorldOffset>,
+        scale: ComputedScale,
+        mut additions: EventWriter<NasaBodyAddition>,
+    ) {
+        use FocusedBody::*;
+
+        if !placement.active {
+            placement.drag = None;
+            return;
+        }
+
+        let scale = scale.get_scale();
+        let offset = relative_world_offset.translation;
+
+        // cursor ray in the simulation frame, relative to the `reference` body
+        let cursor_ray = |reference: usize| {
+            let ray = camera.single().get_ray()?;
+
+            let origin =
+                ray.origin().as_dvec3() / scale + offset - simulation.bodies.positions()[reference];
+
+            Some((origin, ray.direction().as_dvec3()))
+        };
+
+        let intersect = |(origin, direction): (DVec3, DVec3), normal: DVec3| {
+            let t = -origin.dot(normal) / direction.dot(normal);
+
+            (t.is_finite() && t > 0.0).then(|| origin + direction * t)
+        };
+
+        if mouse.just_pressed(MouseButton::Left) && !egui.ctx_mut().is_pointer_over_area() {
+            let Ok(BodyRef(reference)) = bodies.get(focused_body[Primary]) else { return };
+            let Some(i) = simulation.bodies.index(*reference) else { return };
+
+            // plane of the orbit around the dominant attractor, or the ecliptic
+            let normal = simulation
+                .bodies
+                .dominant_attractor(i)
+                .map(|j| {
+                    let r = simulation.bodies.positions()[i] - simulation.bodies.positions()[j];
+                    let v = simulation.bodies.velocities()[i] - simulation.bodies.velocities()[j];
+
+                    r.cross(v).normalize_or_zero()
+                })
+                .filter(|normal| *normal != DVec3::ZERO)
+                .unwrap_or(DVec3::Y);
+
+            let Some(start) = cursor_ray(i).and_then(|ray| intersect(ray, normal)) else { return };
+
+            placement.drag = Some(BodyPlacementDrag {
+                reference: *reference,
+                normal,
+                start,
+                end: start,
+            });
+        }
+
+        let Some(drag) = &placement.drag else { return };
+
+        let normal = drag.normal;
+
+        let Some(reference) = simulation.bodies.index(drag.reference) else {
+            placement.drag = None;
+            return;
+        };
+
+        if mouse.pressed(MouseButton::Left) {
+            let end = cursor_ray(reference).and_then(|ray| intersect(ray, normal));
+
+            if let (Some(end), Some(drag)) = (end, &mut placement.drag) {
+                drag.end = end;
+            }
+        }
+
+        if !mouse.just_released(MouseButton::Left) {
+            return;
+        }
+
+        let Some(velocity) = placement.velocity() else { return };
+        let Some(drag) = placement.drag.take() else { return };
+
+        if simulation.bodies.find(&placement.name).is_some() {
+            screen_print!(sec: 3.0, col: Color::RED, "body [{}] already exists", placement.name);
+            return;
+        }
+
+        screen_print!(sec: 3.0, col: Color::GREEN, "placed [{}]", placement.name);
+
+        additions.send(NasaBodyAddition {
+            date: simulation.time,
+            name: placement.name.clone(),
+            body: SpaceBody {
+                position: simulation.bodies.positions()[reference] + drag.start,
+                velocity: simulation.bodies.velocities()[reference] + velocity,
+                mass: placement.mass,
+                radius: placement.radius,
+                ..default()
+            },
+            material: SpaceBodyKnownDetailsMaterial::TexturePath(placement.texture.clone()),
+        });
+    }
+}
@@ -0,0 +1,98 @@
+use crate::space::simulation::BodyId;
+
+pub struct RemoveBodyRequest(pub BodyId);
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_debug_text_overlay::screen_print;
+    use bevy_ecs_markers::params::MarkerMut;
+
+    use super::RemoveBodyRequest;
+    use crate::space::{
+        display::{BodyRef, BodyTrail},
+        ext::EntityOpsExt,
+        scene::markers::{FocusedBody, MainCamera3d, SelectedBody},
+        simulation::{dominant_body, SpaceSimulation},
+    };
+
+    pub fn remove_body_on_request(
+        mut commands: Commands,
+        mut ev: EventReader<RemoveBodyRequest>,
+        mut simulation: ResMut<SpaceSimulation>,
+        bodies: Query<(Entity, &BodyRef)>,
+        mut trails: Query<(Entity, &mut BodyTrail)>,
+        mut focused: MarkerMut<FocusedBody>,
+        mut selected: MarkerMut<SelectedBody>,
+        camera: Query<Entity, (With<MainCamera3d>, With<Camera3d>)>,
+    ) {
+        use FocusedBody::*;
+        use SelectedBody::*;
+
+        for RemoveBodyRequest(id) in ev.iter() {
+            let Some(name) = simulation.bodies.name(*id).cloned() else { continue };
+
+            simulation.bodies.remove(*id);
+
+            let entity_of = |id| {
+                bodies
+                    .iter()
+                    .find(|(_, BodyRef(body))| *body == id)
+                    .map(|(entity, _)| entity)
+            };
+
+            let Some(entity) = entity_of(*id) else { continue };
+
+            if focused[Secondary] == entity {
+                focused[Secondary].invalidate();
+            }
+
+            // camera moves to the secondary body, or to the heaviest one that is left
+            if focused[Primary] == entity {
+                let next = bodies
+                    .get(focused[Secondary])
+                    .ok()
+                    .map(|(entity, _)| entity)
+                    .or_else(|| {
+                        let index = dominant_body(simulation.bodies.masses())?;
+
+                        entity_of(simulation.bodies.ids()[index])
+                    });
+
+                match next {
+                    Some(next) => {
+                        focused[Primary] = next;
+
+                        commands.entity(camera.single()).set_parent(next);
+                    }
+                    None => {
+                        focused[Primary].invalidate();
+
+                        commands.entity(camera.single()).remove_parent();
+                    }
+                }
+            }
+
+            if selected[CurrentRedirected] == entity || selected[PreviousRedirected] == entity {
+                selected[Current].invalidate();
+                selected[Previous].invalidate();
+                selected[CurrentRedirected].invalidate();
+                selected[PreviousRedirected].invalidate();
+            }
+
+            for (trail_entity, mut trail) in &mut trails {
+                if trail.body == *id {
+                    commands.entity(trail_entity).despawn_recursive();
+                } else if trail.anchor == Some(*id) {
+                    use ringbuffer::RingBufferExt;
+
+                    trail.anchor = None;
+                    trail.trail.clear();
+                }
+            }
+
+            commands.entity(entity).despawn_recursive();
+
+            screen_print!(sec: 3.0, col: Color::GREEN, "removed [{}]", name);
+        }
+    }
+}
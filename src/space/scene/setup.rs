@@ -1,10 +1,11 @@
 pub mod systems {
     use bevy::{pbr::NotShadowCaster, prelude::*, render::view::RenderLayers};
+    use bevy_polyline::prelude::{Polyline, PolylineBundle, PolylineMaterial};
 
     use crate::space::{
         display::{
-            CameraScale, PrimarySelectionRectMarker, RelativeWorldOffset, RelativeWorldScale,
-            SecondarySelectionRectMarker,
+            CameraScale, LagrangePointMarker, PlacementArrow, PrimarySelectionRectMarker,
+            RelativeWorldOffset, RelativeWorldScale, SecondarySelectionRectMarker,
         },
         scene::markers::{BodySystemRoot, CubemapCamera3d},
         simulation::SpaceSimulation,
@@ -15,16 +16,9 @@ pub mod systems {
             controls::camera::CameraControlSensitivity, simulation::SpaceSimulationParams,
         };
 
-        world.insert_resource(SpaceSimulation {
-            G: 6.67e-11,
-            bodies: Default::default(),
-            time: chrono::Utc::now(),
-        });
+        world.insert_resource(SpaceSimulation::default());
 
-        world.insert_resource(SpaceSimulationParams {
-            speed: 86400.0 * 1.0,
-            percision: 4,
-        });
+        world.insert_resource(SpaceSimulationParams::default());
 
         world.insert_resource(CameraScale {
             scale: 1.0 / (147.1 * 1_000_000.0 * 1000.0),
@@ -44,6 +38,8 @@ pub mod systems {
         mut commands: Commands,
         mut meshes: ResMut<Assets<Mesh>>,
         mut materials: ResMut<Assets<StandardMaterial>>,
+        mut polylines: ResMut<Assets<Polyline>>,
+        mut polyline_materials: ResMut<Assets<PolylineMaterial>>,
         camera_scale: Res<CameraScale>,
         asset_server: Res<AssetServer>,
     ) {
@@ -335,6 +331,63 @@ pub mod systems {
                     NotShadowCaster,
                     RenderLayers::layer(2),
                 ));
+
+                let lagrange_point_mesh = meshes.add(
+                    shape::Plane {
+                        size: 16.0,
+                        ..default()
+                    }
+                    .into(),
+                );
+                let lagrange_point_material = materials.add(StandardMaterial {
+                    base_color_texture: Some(asset_server.load("textures/selection_texture.png")),
+                    base_color: Color::LIME_GREEN,
+                    unlit: true,
+                    alpha_mode: AlphaMode::Mask(0.5),
+                    ..default()
+                });
+
+                for k in 0..5 {
+                    commands.spawn((
+                        LagrangePointMarker(k),
+                        PbrBundle {
+                            mesh: lagrange_point_mesh.clone(),
+                            material: lagrange_point_material.clone(),
+                            transform: Transform::from_xyz(0.0, 0.0, -2.0).with_rotation(
+                                Quat::from_euler(
+                                    EulerRot::XYZ,
+                                    std::f32::consts::FRAC_PI_2,
+                                    0.0,
+                                    0.0,
+                                ),
+                            ),
+                            visibility: Visibility { is_visible: false },
+                            ..default()
+                        },
+                        NotShadowCaster,
+                        RenderLayers::layer(2),
+                    ));
+                }
+            });
+
+        commands
+            .entity(bodies_system_entity)
+            .with_children(|commands| {
+                commands.spawn((
+                    PolylineBundle {
+                        polyline: polylines.add(Polyline {
+                            vertices: Vec::with_capacity(5),
+                        }),
+                        material: polyline_materials.add(PolylineMaterial {
+                            width: 3.0,
+                            color: Color::LIME_GREEN,
+                            ..default()
+                        }),
+                        visibility: Visibility { is_visible: false },
+                        ..default()
+                    },
+                    PlacementArrow,
+                ));
             });
     }
 }
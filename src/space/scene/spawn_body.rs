@@ -1,8 +1,18 @@
+use bevy::{prelude::*, utils::HashMap};
+
+// test particles come in thousands, so they share meshes and materials
+#[derive(Default)]
+pub struct TestParticleAssets {
+    schematic: Option<(Handle<Mesh>, Handle<StandardMaterial>)>,
+    solid: HashMap<String, Handle<StandardMaterial>>,
+}
+
 pub mod systems {
-    use bevy::prelude::*;
+    use bevy::{prelude::*, utils::HashSet};
     use bevy_mod_raycast::RaycastMesh;
     use bevy_polyline::prelude::{Polyline, PolylineBundle, PolylineMaterial};
 
+    use super::TestParticleAssets;
     use crate::space::{
         display::{
             BodyRef, BodyTrail, CameraScale, RealisticView, RelativeLightIntensivity,
@@ -10,6 +20,7 @@ pub mod systems {
         },
         nasa_horizons::{NasaBodyAddition, SpaceBodyKnownDetailsMaterial},
         scene::{markers::BodySystemRoot, SelectionRaycastSet, SelectionTargetRedirect},
+        simulation::{BodyId, SpaceSimulation},
     };
 
     pub fn spawn_nasa_body(
@@ -23,11 +34,29 @@ pub mod systems {
         asset_server: Res<AssetServer>,
         mut ev: EventReader<NasaBodyAddition>,
         body_system_root: Query<Entity, With<BodySystemRoot>>,
+        spawned_bodies: Query<&BodyRef>,
+        simulation: Res<SpaceSimulation>,
+        mut particle_assets: Local<TestParticleAssets>,
     ) {
         let body_system_root = body_system_root.single();
 
+        let mut spawned: Option<HashSet<BodyId>> = None;
+
         commands.entity(body_system_root).with_children(|commands| {
             for response in ev.iter() {
+                // bodies are inserted into the simulation before they are spawned
+                let Some(body) = simulation.bodies.find(&response.name) else { continue };
+
+                // additions with a taken name are rejected by the simulation,
+                // the name refers to the body that was already spawned
+                let spawned = spawned.get_or_insert_with(|| {
+                    spawned_bodies.iter().map(|BodyRef(body)| *body).collect()
+                });
+
+                if !spawned.insert(body) {
+                    continue;
+                }
+
                 let mut add_polyline = || {
                     polylines.add(Polyline {
                         vertices: Vec::with_capacity(1024),
@@ -35,7 +64,7 @@ pub mod systems {
                 };
 
                 let mut add_polyline_entity =
-                    |commands: &mut ChildBuilder, polyline, mut color: Color, name: String| {
+                    |commands: &mut ChildBuilder, polyline, mut color: Color, body: BodyId| {
                         color.set_a(0.1);
                         color.set_r((color.r() * 4.5).min(1.0));
                         color.set_g((color.g() * 4.5).min(1.0));
@@ -51,10 +80,7 @@ pub mod systems {
                                     }),
                                     ..Default::default()
                                 },
-                                BodyTrail {
-                                    body_name: name,
-                                    ..default()
-                                },
+                                BodyTrail::new(body),
                             ))
                             .id()
                     };
@@ -62,13 +88,16 @@ pub mod systems {
                 let uv_sphere =
                     || asset_server.load::<Mesh, _>("glb/sphereUV.glb#Mesh0/Primitive0");
 
-                let polyline = add_polyline();
+                // thousands of test particles would make trails unreadable
+                if response.body.mass > 0.0 {
+                    let polyline = add_polyline();
 
-                add_polyline_entity(commands, polyline, Color::GRAY, response.name.clone());
+                    add_polyline_entity(commands, polyline, Color::GRAY, body);
+                }
 
                 commands
                     .spawn((
-                        BodyRef(response.name.clone()),
+                        BodyRef(body),
                         SpatialBundle {
                             transform: Transform::from_translation(Vec3::new(
                                 (response.body.position.x * camera_scale.scale) as f32,
@@ -101,25 +130,56 @@ pub mod systems {
                                 })
                             };
 
-                        anchor.spawn((
+                        let mut make_schematic_mesh = || {
+                            meshes.add(
+                                shape::Icosphere {
+                                    radius: 1.0,
+                                    subdivisions: 2,
+                                }
+                                .into(),
+                            )
+                        };
+
+                        let particle = response.body.mass == 0.0;
+
+                        let (mesh, material) = if particle {
+                            particle_assets
+                                .schematic
+                                .get_or_insert_with(|| {
+                                    (
+                                        make_schematic_mesh(),
+                                        make_schematic_material(&mut materials),
+                                    )
+                                })
+                                .clone()
+                        } else {
+                            (
+                                make_schematic_mesh(),
+                                make_schematic_material(&mut materials),
+                            )
+                        };
+
+                        let parent = anchor.parent_entity();
+
+                        let mut schematic = anchor.spawn((
                             MaterialMeshBundle {
-                                mesh: meshes.add(
-                                    shape::Icosphere {
-                                        radius: 1.0,
-                                        subdivisions: 2,
-                                    }
-                                    .into(),
-                                ),
-                                material: make_schematic_material(&mut materials),
+                                mesh,
+                                material,
                                 transform: Transform::from_scale(Vec3::splat(
                                     (response.body.radius * camera_scale.scale) as f32,
                                 )),
                                 ..default()
                             },
-                            RaycastMesh::<SelectionRaycastSet>::default(),
-                            SelectionTargetRedirect(anchor.parent_entity()),
                             SchematicView,
                         ));
+
+                        // test particles are not picked, selection also recolors the shared material
+                        if !particle {
+                            schematic.insert((
+                                RaycastMesh::<SelectionRaycastSet>::default(),
+                                SelectionTargetRedirect(parent),
+                            ));
+                        }
                         if let SpaceBodyKnownDetailsMaterial::Star(star_material) =
                             &response.material
                         {
@@ -158,7 +218,17 @@ pub mod systems {
                             anchor.spawn((
                                 MaterialMeshBundle {
                                     mesh: uv_sphere(),
-                                    material: make_solid_material(&mut materials, path),
+                                    material: if particle {
+                                        particle_assets
+                                            .solid
+                                            .entry(path.to_string())
+                                            .or_insert_with(|| {
+                                                make_solid_material(&mut materials, path)
+                                            })
+                                            .clone()
+                                    } else {
+                                        make_solid_material(&mut materials, path)
+                                    },
                                     transform: Transform::from_scale(Vec3::splat(
                                         (response.body.radius * camera_scale.scale) as f32,
                                     ))
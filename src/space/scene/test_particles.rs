@@ -0,0 +1,46 @@
+use crate::space::simulation::TestParticleRing;
+
+pub struct SpawnTestParticlesRequest(pub TestParticleRing);
+
+pub mod systems {
+    use bevy::prelude::*;
+    use bevy_debug_text_overlay::screen_print;
+
+    use super::SpawnTestParticlesRequest;
+    use crate::space::{
+        nasa_horizons::{NasaBodyAddition, SpaceBodyKnownDetailsMaterial},
+        simulation::SpaceSimulation,
+    };
+
+    pub fn spawn_test_particles_on_request(
+        mut ev: EventReader<SpawnTestParticlesRequest>,
+        simulation: Res<SpaceSimulation>,
+        mut additions: EventWriter<NasaBodyAddition>,
+        mut batch: Local<usize>,
+    ) {
+        for SpawnTestParticlesRequest(ring) in ev.iter() {
+            // names of particles must not clash with previously spawned ones
+            let mut ring = ring.clone();
+            ring.name = format!("{} #{}", ring.name, *batch);
+            *batch += 1;
+
+            let Some(particles) = ring.generate(&simulation.bodies, simulation.G) else {
+                screen_print!(sec: 3.0, col: Color::RED, "no body to spawn particles around");
+                continue;
+            };
+
+            let center = simulation.bodies.name(ring.center).unwrap();
+
+            screen_print!(sec: 3.0, col: Color::GREEN, "spawned {} particles around [{center}]", particles.len());
+
+            additions.send_batch(particles.into_iter().map(|(name, body)| NasaBodyAddition {
+                date: simulation.time,
+                name,
+                body,
+                material: SpaceBodyKnownDetailsMaterial::TexturePath(
+                    "textures/asteroid.jpg".into(),
+                ),
+            }));
+        }
+    }
+}
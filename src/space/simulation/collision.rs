@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+
+use super::{BodyId, SpaceBodies};
+
+#[derive(Debug, Clone)]
+pub struct BodiesCollided {
+    pub survivor: BodyId,
+    pub absorbed: BodyId,
+    // the absorbed body can not be looked up by its handle anymore
+    pub survivor_name: String,
+    pub absorbed_name: String,
+    pub time: DateTime<Utc>,
+}
+
+impl SpaceBodies {
+    pub fn find_overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        let positions = self.positions();
+        let radiuses = self.radiuses();
+
+        // sweep and prune along x axis
+        let mut order = (0..self.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|i, j| {
+            (positions[*i].x - radiuses[*i]).total_cmp(&(positions[*j].x - radiuses[*j]))
+        });
+
+        let mut pairs = vec![];
+
+        for (k, &i) in order.iter().enumerate() {
+            let end = positions[i].x + radiuses[i];
+
+            for &j in order[k + 1..]
+                .iter()
+                .take_while(|j| positions[**j].x - radiuses[**j] <= end)
+            {
+                // test particles pass through each other
+                if self.masses()[i] == 0.0 && self.masses()[j] == 0.0 {
+                    continue;
+                }
+
+                let reach = radiuses[i] + radiuses[j];
+
+                if positions[i].distance_squared(positions[j]) < reach * reach {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    // perfectly inelastic merge: lighter body is absorbed by the heavier one,
+    // mass, momentum and volume are conserved
+    pub fn merge(&mut self, survivor: usize, absorbed: usize) {
+        let m1 = self.masses()[survivor];
+        let m2 = self.masses()[absorbed];
+        let mass = m1 + m2;
+
+        let position = (self.positions()[survivor] * m1 + self.positions()[absorbed] * m2) / mass;
+        let velocity = (self.velocities()[survivor] * m1 + self.velocities()[absorbed] * m2) / mass;
+        let radius = (self.radiuses()[survivor].powi(3) + self.radiuses()[absorbed].powi(3)).cbrt();
+
+        self.masses_mut()[survivor] = mass;
+        self.positions_mut()[survivor] = position;
+        self.velocities_mut()[survivor] = velocity;
+        self.radiuses_mut()[survivor] = radius;
+    }
+
+    pub fn merge_overlapping(&mut self, time: DateTime<Utc>) -> Vec<BodiesCollided> {
+        let pairs = self.find_overlapping_pairs();
+
+        if pairs.is_empty() {
+            return vec![];
+        }
+
+        let ids = self.ids().clone();
+
+        let mut collisions = vec![];
+
+        for (a, b) in pairs {
+            // either body may already be merged into another one
+            let (Some(i), Some(j)) = (self.index(ids[a]), self.index(ids[b])) else { continue };
+
+            let (survivor, absorbed) = if self.masses()[i] >= self.masses()[j] {
+                (i, j)
+            } else {
+                (j, i)
+            };
+
+            let collision = BodiesCollided {
+                survivor: self.ids()[survivor],
+                absorbed: self.ids()[absorbed],
+                survivor_name: self.names()[survivor].clone(),
+                absorbed_name: self.names()[absorbed].clone(),
+                time,
+            };
+
+            self.merge(survivor, absorbed);
+            self.remove(collision.absorbed);
+
+            collisions.push(collision);
+        }
+
+        collisions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+    use chrono::Utc;
+
+    use crate::space::simulation::{SpaceBodies, SpaceBody};
+
+    #[test]
+    fn merge_conserves_mass_momentum_and_volume() {
+        let mut bodies = SpaceBodies::default();
+
+        let heavy = SpaceBody {
+            position: DVec3::new(0.0, 0.0, 0.0),
+            velocity: DVec3::new(1.0, 2.0, 0.0),
+            mass: 3.0,
+            radius: 2.0,
+            ..Default::default()
+        };
+        let light = SpaceBody {
+            position: DVec3::new(3.0, 0.0, 0.0),
+            velocity: DVec3::new(-3.0, 0.0, 4.0),
+            mass: 1.0,
+            radius: 1.5,
+            ..Default::default()
+        };
+        // far from both, must not be touched
+        let other = SpaceBody {
+            position: DVec3::new(1e6, 0.0, 0.0),
+            mass: 5.0,
+            radius: 1.0,
+            ..Default::default()
+        };
+
+        let momentum = heavy.velocity * heavy.mass + light.velocity * light.mass;
+        let mass_center = (heavy.position * heavy.mass + light.position * light.mass) / 4.0;
+
+        let light_id = bodies.insert("light".into(), light).unwrap();
+        let heavy_id = bodies.insert("heavy".into(), heavy).unwrap();
+        bodies.insert("other".into(), other).unwrap();
+
+        let collisions = bodies.merge_overlapping(Utc::now());
+
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].survivor, heavy_id);
+        assert_eq!(collisions[0].absorbed, light_id);
+
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies.index(light_id).is_none());
+
+        let i = bodies.index(heavy_id).unwrap();
+
+        assert_eq!(bodies.masses()[i], 4.0);
+        assert!((bodies.velocities()[i] * 4.0).distance(momentum) < 1e-12);
+        assert!(bodies.positions()[i].distance(mass_center) < 1e-12);
+        assert!((bodies.radiuses()[i].powi(3) - (8.0 + 3.375)).abs() < 1e-12);
+
+        // total momentum of the system did not change
+        let total = (0..bodies.len())
+            .map(|k| bodies.velocities()[k] * bodies.masses()[k])
+            .sum::<DVec3>();
+
+        assert!(total.distance(momentum) < 1e-12);
+    }
+}
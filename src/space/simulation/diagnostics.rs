@@ -0,0 +1,163 @@
+use bevy::{math::DVec3, prelude::*};
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use ringbuffer::AllocRingBuffer;
+
+use super::SpaceBodies;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpaceBodiesInvariants {
+    pub kinetic_energy: f64,
+    pub potential_energy: f64,
+    pub momentum: DVec3,
+    pub angular_momentum: DVec3,
+    pub barycenter: DVec3,
+    pub mass: f64,
+}
+
+impl SpaceBodiesInvariants {
+    pub fn energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+}
+
+impl SpaceBodies {
+    pub fn invariants(&self, g: f64) -> SpaceBodiesInvariants {
+        let positions = self.positions();
+        let velocities = self.velocities();
+        let masses = self.masses();
+
+        // test particles do not contribute to potential energy
+        let sources = (0..self.len())
+            .filter(|i| masses[*i] > 0.0)
+            .collect::<Vec<_>>();
+
+        let potential_energy = (0..sources.len())
+            .into_par_iter()
+            .map(|k| {
+                let i = sources[k];
+
+                sources[k + 1..]
+                    .iter()
+                    .map(|&j| -g * masses[i] * masses[j] / positions[i].distance(positions[j]))
+                    .sum::<f64>()
+            })
+            .sum();
+
+        let mut invariants = SpaceBodiesInvariants {
+            potential_energy,
+            ..default()
+        };
+
+        for ((p, v), m) in positions.iter().zip(velocities.iter()).zip(masses.iter()) {
+            invariants.kinetic_energy += 0.5 * m * v.length_squared();
+            invariants.momentum += *v * *m;
+            invariants.angular_momentum += p.cross(*v * *m);
+            invariants.barycenter += *p * *m;
+            invariants.mass += m;
+        }
+
+        if invariants.mass > 0.0 {
+            invariants.barycenter /= invariants.mass;
+        }
+
+        invariants
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpaceSimulationDrift {
+    pub energy: f64,
+    pub momentum: f64,
+    pub angular_momentum: f64,
+    pub barycenter: f64,
+}
+
+#[derive(Resource, Default)]
+pub struct SpaceSimulationDiagnostics {
+    pub initial: Option<(DateTime<Utc>, SpaceBodiesInvariants)>,
+    pub current: SpaceBodiesInvariants,
+    pub drift: SpaceSimulationDrift,
+    // (days since initial, relative energy drift)
+    pub history: AllocRingBuffer<[f64; 2]>,
+    bodies_count: usize,
+    last_time: Option<DateTime<Utc>>,
+}
+
+impl SpaceSimulationDiagnostics {
+    pub fn reset(&mut self) {
+        use ringbuffer::RingBufferExt;
+
+        self.initial = None;
+        self.history.clear();
+    }
+
+    pub fn update(&mut self, bodies: &SpaceBodies, time: DateTime<Utc>, g: f64) {
+        use ringbuffer::RingBufferWrite;
+
+        // merges, insertions and removals legitimately change the invariants
+        if self.bodies_count != bodies.len() {
+            self.bodies_count = bodies.len();
+            self.reset();
+        }
+
+        if self.last_time == Some(time) && self.initial.is_some() {
+            return;
+        }
+        self.last_time = Some(time);
+
+        self.current = bodies.invariants(g);
+
+        let &mut (initial_time, initial) = self.initial.get_or_insert((time, self.current));
+
+        let relative = |current: f64, initial: f64| {
+            if initial == 0.0 {
+                current
+            } else {
+                (current - initial) / initial.abs()
+            }
+        };
+        let relative_vec = |current: DVec3, initial: DVec3| {
+            let change = current.distance(initial);
+            let initial = initial.length();
+            if initial == 0.0 {
+                change
+            } else {
+                change / initial
+            }
+        };
+
+        let elapsed = (time - initial_time).num_milliseconds() as f64 / 1000.0;
+
+        // barycenter is expected to move uniformly with the total momentum
+        let expected_barycenter = if initial.mass > 0.0 {
+            initial.barycenter + initial.momentum / initial.mass * elapsed
+        } else {
+            initial.barycenter
+        };
+
+        self.drift = SpaceSimulationDrift {
+            energy: relative(self.current.energy(), initial.energy()),
+            momentum: relative_vec(self.current.momentum, initial.momentum),
+            angular_momentum: relative_vec(self.current.angular_momentum, initial.angular_momentum),
+            barycenter: self.current.barycenter.distance(expected_barycenter),
+        };
+
+        self.history
+            .push([elapsed / (24.0 * 60.0 * 60.0), self.drift.energy]);
+    }
+}
+
+pub mod systems {
+    use bevy::prelude::*;
+
+    use super::SpaceSimulationDiagnostics;
+    use crate::space::simulation::SpaceSimulation;
+
+    pub fn update_diagnostics(
+        mut diagnostics: ResMut<SpaceSimulationDiagnostics>,
+        simulation: Res<SpaceSimulation>,
+    ) {
+        diagnostics.update(&simulation.bodies, simulation.time, simulation.G);
+    }
+}
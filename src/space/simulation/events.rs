@@ -0,0 +1,466 @@
+use bevy::{math::DVec3, prelude::*};
+use chrono::{DateTime, Duration, Utc};
+use ringbuffer::AllocRingBuffer;
+
+use super::{BodiesCollided, BodyId, SpaceBodies, SpaceSimulation, SpaceSimulationParams};
+
+const BISECTION_ITERATIONS: usize = 48;
+
+// angle in radians the relative motion may turn by within a step,
+// apsides and nodes are half a turn apart, so a step passes at most one of them
+const MAX_STEP_ANGLE: f64 = 0.5;
+
+// steps are split in no more than this many parts, however close bodies get
+const MAX_STEP_SPLITS: f64 = 64.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceEventDetectorKind {
+    // periapsis and apoapsis passages around the other body
+    Apsides,
+    // crossings of the ecliptic plane passing through the other body
+    Nodes,
+    ClosestApproach,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceEventDetector {
+    pub kind: SpaceEventDetectorKind,
+    pub body: BodyId,
+    pub other: BodyId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceEventKind {
+    Periapsis,
+    Apoapsis,
+    AscendingNode,
+    DescendingNode,
+    ClosestApproach,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpaceEvent {
+    pub time: DateTime<Utc>,
+    pub kind: SpaceEventKind,
+    pub body: BodyId,
+    pub other: BodyId,
+    // bodies may be removed while the event stays in the log
+    pub body_name: String,
+    pub other_name: String,
+    pub distance: f64,
+}
+
+pub struct SpaceSimulationEventDetected(pub SpaceEvent);
+
+// relative motion of the body within a step, cubic Hermite interpolation
+// between states at its ends, `s` runs from 0 to 1 over the step of `h` seconds
+struct StepMotion {
+    before: (DVec3, DVec3),
+    after: (DVec3, DVec3),
+    h: f64,
+}
+
+impl StepMotion {
+    fn position(&self, s: f64) -> DVec3 {
+        let ((r0, v0), (r1, v1), h) = (self.before, self.after, self.h);
+        let (s2, s3) = (s * s, s * s * s);
+
+        (2.0 * s3 - 3.0 * s2 + 1.0) * r0
+            + (s3 - 2.0 * s2 + s) * h * v0
+            + (3.0 * s2 - 2.0 * s3) * r1
+            + (s3 - s2) * h * v1
+    }
+
+    // derivative of the position with respect to `s`
+    fn tangent(&self, s: f64) -> DVec3 {
+        let ((r0, v0), (r1, v1), h) = (self.before, self.after, self.h);
+        let s2 = s * s;
+
+        (6.0 * s2 - 6.0 * s) * r0
+            + (3.0 * s2 - 4.0 * s + 1.0) * h * v0
+            + (6.0 * s - 6.0 * s2) * r1
+            + (3.0 * s2 - 2.0 * s) * h * v1
+    }
+
+    // point where `f` changes sign over the step, with the direction of the change,
+    // zero counts as positive, so an event at the end of a step is not found again,
+    // only ends of the step are compared, since velocities of low order integrators
+    // lag behind and make the interpolation wiggle in between, so at most one change
+    // is found, steps are kept short enough by `SpaceSimulationEvents::max_step`
+    fn root(&self, f: impl Fn(&Self, f64) -> f64) -> Option<(f64, bool)> {
+        let (mut a, mut b) = (0.0, 1.0);
+        let (fa, fb) = (f(self, a), f(self, b));
+
+        if (fa < 0.0) == (fb < 0.0) {
+            return None;
+        }
+
+        for _ in 0..BISECTION_ITERATIONS {
+            let middle = (a + b) / 2.0;
+
+            if (f(self, middle) < 0.0) == (fa < 0.0) {
+                a = middle;
+            } else {
+                b = middle;
+            }
+        }
+
+        Some(((a + b) / 2.0, fb >= 0.0))
+    }
+}
+
+impl SpaceEventDetector {
+    // position and velocity of the body relative to the other one
+    fn relative_state(&self, bodies: &SpaceBodies) -> Option<(DVec3, DVec3)> {
+        let i = bodies.index(self.body)?;
+        let j = bodies.index(self.other)?;
+
+        Some((
+            bodies.positions()[i] - bodies.positions()[j],
+            bodies.velocities()[i] - bodies.velocities()[j],
+        ))
+    }
+
+    fn detect(&self, motion: &StepMotion) -> Option<(f64, SpaceEventKind)> {
+        use SpaceEventKind::*;
+
+        // distance grows along the step where it is positive, in either direction of time
+        let radial = |m: &StepMotion, s: f64| m.position(s).dot(m.tangent(s));
+        // height above the ecliptic is the negated simulation y
+        let height = |m: &StepMotion, s: f64| -m.position(s).y;
+
+        match self.kind {
+            SpaceEventDetectorKind::Apsides => motion
+                .root(radial)
+                .map(|(s, rising)| (s, if rising { Periapsis } else { Apoapsis })),
+            SpaceEventDetectorKind::ClosestApproach => motion
+                .root(radial)
+                .filter(|(_, rising)| *rising)
+                .map(|(s, _)| (s, ClosestApproach)),
+            SpaceEventDetectorKind::Nodes => motion.root(height).map(|(s, rising)| {
+                if rising == (motion.h > 0.0) {
+                    (s, AscendingNode)
+                } else {
+                    (s, DescendingNode)
+                }
+            }),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct SpaceSimulationEvents {
+    pub detectors: Vec<SpaceEventDetector>,
+    // simulation stops and seeks to the first event it passes
+    pub pause_on_events: bool,
+    log: AllocRingBuffer<SpaceEvent>,
+}
+
+impl Default for SpaceSimulationEvents {
+    fn default() -> Self {
+        Self {
+            detectors: vec![],
+            pause_on_events: false,
+            log: AllocRingBuffer::with_capacity(256),
+        }
+    }
+}
+
+impl SpaceSimulationEvents {
+    pub fn log(&self) -> impl DoubleEndedIterator<Item = &SpaceEvent> {
+        use ringbuffer::RingBufferExt;
+
+        self.log.iter()
+    }
+
+    pub fn clear_log(&mut self) {
+        use ringbuffer::RingBufferExt;
+
+        self.log.clear();
+    }
+
+    pub fn add_detector(&mut self, detector: SpaceEventDetector) {
+        if !self.detectors.contains(&detector) {
+            self.detectors.push(detector);
+        }
+    }
+
+    // relative states watched by detectors, taken before a step
+    pub fn states(&self, bodies: &SpaceBodies) -> Vec<Option<(DVec3, DVec3)>> {
+        self.detectors
+            .iter()
+            .map(|detector| detector.relative_state(bodies))
+            .collect()
+    }
+
+    // longest step that passes at most one event of every detector, relative position
+    // turns by no more than |v| / |r| radians per second, and velocity, pulled by
+    // the pair alone, by no more than G (m + M) / (|r|² |v|), which is faster near apoapsis
+    pub fn max_step(&self, simulation: &SpaceSimulation) -> f64 {
+        let bodies = &simulation.bodies;
+
+        self.detectors
+            .iter()
+            .filter_map(|detector| {
+                let (r, v) = detector.relative_state(bodies)?;
+                let mass = bodies.masses()[bodies.index(detector.body)?]
+                    + bodies.masses()[bodies.index(detector.other)?];
+
+                let position_rate = v.length() / r.length();
+                let velocity_rate = simulation.G * mass / (r.length_squared() * v.length());
+
+                Some(MAX_STEP_ANGLE / position_rate.max(velocity_rate))
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // takes the step of `delta_seconds` and finds events within it, the step is split
+    // in whole milliseconds to not pass more than one event of a detector
+    pub fn take_step(
+        &mut self,
+        simulation: &mut SpaceSimulation,
+        params: &SpaceSimulationParams,
+        delta_seconds: f64,
+    ) -> (Vec<BodiesCollided>, Vec<SpaceEvent>) {
+        // shortest sub-step, however close bodies get
+        let min_step = delta_seconds.abs() / MAX_STEP_SPLITS;
+
+        let mut remaining = (delta_seconds * 1000.0).round() as i64;
+
+        let mut collisions = vec![];
+        let mut found = vec![];
+
+        while remaining != 0 {
+            // motion speeds up towards periapsis, so the limit follows the current state
+            let max_step = self.max_step(simulation).max(min_step);
+            let max_milliseconds = ((max_step * 1000.0) as i64).max(1);
+
+            let milliseconds = remaining.abs().min(max_milliseconds) * remaining.signum();
+            let delta_seconds = milliseconds as f64 / 1000.0;
+
+            let before = self.states(&simulation.bodies);
+            let start = simulation.time;
+
+            collisions.extend(simulation.take_step_smooth(params, delta_seconds));
+            found.extend(self.detect(&before, &simulation.bodies, start, delta_seconds));
+
+            remaining -= milliseconds;
+        }
+
+        (collisions, found)
+    }
+
+    // finds events within the step of `delta_seconds` from the `start`, `before` are states
+    // taken before it, events are logged and returned in the order they happened
+    pub fn detect(
+        &mut self,
+        before: &[Option<(DVec3, DVec3)>],
+        bodies: &SpaceBodies,
+        start: DateTime<Utc>,
+        delta_seconds: f64,
+    ) -> Vec<SpaceEvent> {
+        use ringbuffer::{RingBufferExt, RingBufferWrite};
+
+        let mut found = vec![];
+
+        for (detector, before) in self.detectors.iter().zip(before) {
+            // bodies may have merged or been removed during the step
+            let Some(before) = *before else { continue };
+            let Some(after) = detector.relative_state(bodies) else { continue };
+
+            let motion = StepMotion {
+                before,
+                after,
+                h: delta_seconds,
+            };
+
+            if let Some((s, kind)) = detector.detect(&motion) {
+                found.push((
+                    s,
+                    SpaceEvent {
+                        time: start + Duration::milliseconds((s * delta_seconds * 1000.0) as i64),
+                        kind,
+                        body: detector.body,
+                        other: detector.other,
+                        body_name: bodies.name(detector.body).cloned().unwrap_or_default(),
+                        other_name: bodies.name(detector.other).cloned().unwrap_or_default(),
+                        distance: motion.position(s).length(),
+                    },
+                ));
+            }
+        }
+
+        found.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        // replayed steps find the same events again, a step after seeking
+        // may start at a different time, so they only match within a step
+        let tolerance = Duration::milliseconds((delta_seconds.abs() * 1000.0) as i64);
+
+        let found = found
+            .into_iter()
+            .map(|(_, event)| event)
+            .filter(|event| {
+                !self.log.iter().any(|logged| {
+                    logged.kind == event.kind
+                        && logged.body == event.body
+                        && logged.other == event.other
+                        && (logged.time - event.time).abs() < tolerance
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for event in &found {
+            self.log.push(event.clone());
+        }
+
+        found
+    }
+}
+
+pub mod systems {
+    use bevy::prelude::*;
+
+    use super::{SpaceSimulationEventDetected, SpaceSimulationEvents};
+    use crate::space::simulation::{
+        SeekSpaceSimulationRequest, SpaceSimulationParams, SpaceSimulationState,
+    };
+
+    pub fn pause_on_events(
+        mut ev: EventReader<SpaceSimulationEventDetected>,
+        events: Res<SpaceSimulationEvents>,
+        params: Res<SpaceSimulationParams>,
+        mut state: ResMut<State<SpaceSimulationState>>,
+        mut seek_ev: EventWriter<SeekSpaceSimulationRequest>,
+    ) {
+        let detected = ev.iter().map(|SpaceSimulationEventDetected(event)| event);
+
+        // steps of the frame went past the event, the first one in the playback direction
+        // is returned to, events are read anyway, so enabling the pause later
+        // does not return to events of past frames
+        let first = if params.speed < 0.0 {
+            detected.max_by_key(|event| event.time)
+        } else {
+            detected.min_by_key(|event| event.time)
+        };
+
+        if !events.pause_on_events {
+            return;
+        }
+
+        let Some(event) = first else { return };
+
+        if *state.current() == SpaceSimulationState::Running {
+            state.overwrite_set(SpaceSimulationState::Stopped).unwrap();
+        }
+
+        seek_ev.send(SeekSpaceSimulationRequest { time: event.time });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{
+        SpaceEvent, SpaceEventDetector, SpaceEventDetectorKind, SpaceEventKind,
+        SpaceSimulationEvents,
+    };
+    use crate::space::simulation::{
+        OrbitalElements, SpaceBody, SpaceSimulation, SpaceSimulationIntegrator,
+        SpaceSimulationParams,
+    };
+
+    // apsides of the planet around the star found within a single step of `periods`,
+    // returned with the orbital period
+    fn apsides(eccentricity: f64, mean_anomaly: f64, periods: f64) -> (Vec<SpaceEvent>, f64) {
+        let mut simulation = SpaceSimulation {
+            time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            G: 1.0,
+            ..Default::default()
+        };
+
+        let elements = OrbitalElements {
+            semi_major_axis: 1000.0,
+            eccentricity,
+            mean_anomaly,
+            ..Default::default()
+        };
+        let (position, velocity) = elements.to_state(1.0);
+
+        let star = simulation
+            .bodies
+            .insert(
+                "star".into(),
+                SpaceBody {
+                    mass: 1.0,
+                    radius: 1.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let planet = simulation
+            .bodies
+            .insert(
+                "planet".into(),
+                SpaceBody {
+                    position,
+                    velocity,
+                    radius: 1.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let mut events = SpaceSimulationEvents::default();
+        events.add_detector(SpaceEventDetector {
+            kind: SpaceEventDetectorKind::Apsides,
+            body: planet,
+            other: star,
+        });
+
+        let params = SpaceSimulationParams {
+            integrator: SpaceSimulationIntegrator::RungeKutta4,
+            percision: 16,
+            ..Default::default()
+        };
+
+        let period = std::f64::consts::TAU / elements.mean_motion(1.0);
+        let (_, found) = events.take_step(&mut simulation, &params, periods * period);
+
+        (found, period)
+    }
+
+    #[test]
+    fn long_step_finds_every_apsis() {
+        use SpaceEventKind::*;
+
+        // three orbits at once, there are six apsides passed
+        let (found, period) = apsides(0.5, 0.3, 3.0);
+
+        let kinds = found.iter().map(|event| event.kind).collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            [Apoapsis, Periapsis, Apoapsis, Periapsis, Apoapsis, Periapsis]
+        );
+
+        // apsides are half an orbit apart
+        for pair in found.windows(2) {
+            let between = (pair[1].time - pair[0].time).num_milliseconds() as f64 / 1000.0;
+
+            assert!((between - period / 2.0).abs() < 1e-3 * period);
+        }
+    }
+
+    #[test]
+    fn step_from_apoapsis_finds_periapsis() {
+        use SpaceEventKind::*;
+
+        // slow motion at the apoapsis must not set the pace through the periapsis
+        let (found, _) = apsides(0.9, 3.0, 0.75);
+
+        let kinds = found.iter().map(|event| event.kind).collect::<Vec<_>>();
+
+        assert_eq!(kinds, [Apoapsis, Periapsis]);
+    }
+}
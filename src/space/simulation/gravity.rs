@@ -0,0 +1,444 @@
+use bevy::math::DVec3;
+use rayon::prelude::*;
+
+use super::{BurnForce, SpaceBodyNonGravitational, SpaceBodyZonalHarmonics, SpaceSimulationParams};
+
+// below this amount of bodies octree construction costs more than it saves
+pub const BARNES_HUT_MIN_BODIES: usize = 128;
+
+const OCTREE_MAX_DEPTH: usize = 48;
+
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SpaceSimulationForceBackend {
+    #[default]
+    Direct,
+    BarnesHut,
+}
+
+impl SpaceSimulationForceBackend {
+    pub const ALL: [SpaceSimulationForceBackend; 2] = [
+        SpaceSimulationForceBackend::Direct,
+        SpaceSimulationForceBackend::BarnesHut,
+    ];
+}
+
+impl std::str::FromStr for SpaceSimulationForceBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| format!("{backend:?}").eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow::anyhow!("Unknown force backend: [{s}]"))
+    }
+}
+
+// everything but the state that determines accelerations of bodies
+pub struct ForceModel<'a> {
+    pub params: &'a SpaceSimulationParams,
+    pub g: f64,
+    pub masses: &'a [f64],
+    pub zonal_harmonics: &'a [Option<SpaceBodyZonalHarmonics>],
+    pub non_gravitational: &'a [Option<SpaceBodyNonGravitational>],
+    pub burns: &'a [BurnForce],
+}
+
+impl<'a> ForceModel<'a> {
+    pub fn accelerations(&self, positions: &[DVec3], velocities: &[DVec3]) -> Vec<DVec3> {
+        let &ForceModel {
+            params, g, masses, ..
+        } = self;
+
+        // relativistic correction and non gravitational forces are computed
+        // only relative to the dominant mass, i.e. the Sun
+        let dominant = (params.post_newtonian || params.non_gravitational)
+            .then(|| dominant_body(masses))
+            .flatten();
+
+        // test particles feel gravity but exert none
+        let sources = (0..masses.len())
+            .filter(|i| masses[*i] > 0.0)
+            .collect::<Vec<_>>();
+
+        let oblate = if params.zonal_harmonics {
+            self.zonal_harmonics
+                .iter()
+                .enumerate()
+                .filter_map(|(i, harmonics)| Some((i, harmonics.as_ref()?)))
+                .collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        let correction = |i: usize| {
+            let mut a = self.zonal_harmonics_correction(i, &oblate, positions);
+
+            for burn in self.burns.iter().filter(|burn| burn.body == i) {
+                a += burn.acceleration(positions, velocities);
+            }
+
+            let Some(d) = dominant.filter(|d| *d != i) else { return a };
+
+            let r = positions[i] - positions[d];
+            let v = velocities[i] - velocities[d];
+
+            if params.post_newtonian {
+                a += post_newtonian_correction(g, masses[d], r, v);
+            }
+
+            if let Some(model) = self.non_gravitational[i].filter(|_| params.non_gravitational) {
+                a += model.acceleration(r, v);
+            }
+
+            a
+        };
+
+        match params.force_backend {
+            SpaceSimulationForceBackend::BarnesHut if sources.len() >= BARNES_HUT_MIN_BODIES => {
+                Octree::new(masses, positions, &sources).accelerations(
+                    g,
+                    params.barnes_hut_theta,
+                    positions,
+                    correction,
+                )
+            }
+            _ => direct_accelerations(g, masses, positions, &sources, correction),
+        }
+    }
+
+    // field of oblate bodies acting on the body `i` and reaction of the body `i`
+    // on them if it is oblate itself, so momentum is conserved
+    fn zonal_harmonics_correction(
+        &self,
+        i: usize,
+        oblate: &[(usize, &SpaceBodyZonalHarmonics)],
+        positions: &[DVec3],
+    ) -> DVec3 {
+        let &ForceModel {
+            params, g, masses, ..
+        } = self;
+
+        let within_distance = |harmonics: &SpaceBodyZonalHarmonics, r: DVec3| {
+            r.length_squared()
+                < (params.zonal_harmonics_distance * harmonics.reference_radius).powi(2)
+        };
+
+        let mut a = DVec3::ZERO;
+
+        for &(k, harmonics) in oblate.iter().filter(|(k, _)| *k != i) {
+            let r = positions[i] - positions[k];
+
+            if within_distance(harmonics, r) {
+                a += harmonics.acceleration(g, masses[k], r);
+            }
+        }
+
+        if let Some(harmonics) = self.zonal_harmonics[i]
+            .as_ref()
+            .filter(|_| params.zonal_harmonics)
+        {
+            for j in (0..positions.len()).filter(|j| *j != i) {
+                let r = positions[j] - positions[i];
+
+                if within_distance(harmonics, r) {
+                    a -= harmonics.acceleration(g, masses[i], r) * (masses[j] / masses[i]);
+                }
+            }
+        }
+
+        a
+    }
+}
+
+pub fn direct_accelerations(
+    g: f64,
+    masses: &[f64],
+    positions: &[DVec3],
+    sources: &[usize],
+    correction: impl Fn(usize) -> DVec3 + Sync,
+) -> Vec<DVec3> {
+    positions
+        .par_iter()
+        .enumerate()
+        .map(|(i, p1)| {
+            sources
+                .iter()
+                .filter(|j| i != **j)
+                .map(|j| attraction(g, masses[*j], positions[*j] - *p1))
+                .sum::<DVec3>()
+                + correction(i)
+        })
+        .collect()
+}
+
+pub fn dominant_body(masses: &[f64]) -> Option<usize> {
+    masses
+        .iter()
+        .enumerate()
+        .max_by(|(_, m1), (_, m2)| m1.total_cmp(m2))
+        .map(|(i, _)| i)
+}
+
+// first order post-newtonian term of a test body in the field of `mass`,
+// `r` and `v` are relative to the attracting body
+fn post_newtonian_correction(g: f64, mass: f64, r: DVec3, v: DVec3) -> DVec3 {
+    let gm = g * mass;
+    let distance = r.length();
+
+    gm / (SPEED_OF_LIGHT * SPEED_OF_LIGHT * distance.powi(3))
+        * ((4.0 * gm / distance - v.length_squared()) * r + 4.0 * r.dot(v) * v)
+}
+
+fn attraction(g: f64, mass: f64, r: DVec3) -> DVec3 {
+    let distance_squared = r.length_squared();
+
+    r * (g * mass / (distance_squared * distance_squared.sqrt()))
+}
+
+enum OctreeNodeKind {
+    Empty,
+    Leaf(Vec<usize>),
+    Internal(usize),
+}
+
+struct OctreeNode {
+    center: DVec3,
+    half_size: f64,
+    mass: f64,
+    mass_center: DVec3,
+    kind: OctreeNodeKind,
+}
+
+impl OctreeNode {
+    fn new(center: DVec3, half_size: f64) -> Self {
+        Self {
+            center,
+            half_size,
+            mass: 0.0,
+            mass_center: DVec3::ZERO,
+            kind: OctreeNodeKind::Empty,
+        }
+    }
+
+    fn octant(&self, position: DVec3) -> usize {
+        (position.x >= self.center.x) as usize
+            | ((position.y >= self.center.y) as usize) << 1
+            | ((position.z >= self.center.z) as usize) << 2
+    }
+
+    fn contains(&self, position: DVec3) -> bool {
+        (position - self.center).abs().max_element() <= self.half_size
+    }
+}
+
+pub struct Octree<'a> {
+    nodes: Vec<OctreeNode>,
+    masses: &'a [f64],
+}
+
+impl<'a> Octree<'a> {
+    pub fn new(masses: &'a [f64], positions: &[DVec3], sources: &[usize]) -> Self {
+        let (min, max) = sources.iter().map(|i| positions[*i]).fold(
+            (DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY)),
+            |(min, max), p| (min.min(p), max.max(p)),
+        );
+
+        let center = (min + max) / 2.0;
+        let half_size = ((max - min).max_element() / 2.0).max(1.0);
+
+        let mut tree = Self {
+            nodes: vec![OctreeNode::new(center, half_size)],
+            masses,
+        };
+
+        for &i in sources {
+            tree.insert(0, i, positions, 0);
+        }
+
+        tree.aggregate(0, positions);
+
+        tree
+    }
+
+    fn insert(&mut self, node: usize, body: usize, positions: &[DVec3], depth: usize) {
+        match &mut self.nodes[node].kind {
+            OctreeNodeKind::Empty => {
+                self.nodes[node].kind = OctreeNodeKind::Leaf(vec![body]);
+            }
+            OctreeNodeKind::Leaf(bodies) if depth >= OCTREE_MAX_DEPTH => {
+                bodies.push(body);
+            }
+            OctreeNodeKind::Leaf(bodies) => {
+                let bodies = std::mem::take(bodies);
+
+                self.subdivide(node);
+
+                for b in bodies.into_iter().chain(std::iter::once(body)) {
+                    self.insert(node, b, positions, depth);
+                }
+            }
+            OctreeNodeKind::Internal(first_child) => {
+                let first_child = *first_child;
+                let child = first_child + self.nodes[node].octant(positions[body]);
+
+                self.insert(child, body, positions, depth + 1);
+            }
+        }
+    }
+
+    fn subdivide(&mut self, node: usize) {
+        let first_child = self.nodes.len();
+        let center = self.nodes[node].center;
+        let half_size = self.nodes[node].half_size / 2.0;
+
+        for octant in 0..8 {
+            let sign = |bit: usize| if octant & bit != 0 { 1.0 } else { -1.0 };
+            let offset = DVec3::new(sign(1), sign(2), sign(4)) * half_size;
+
+            self.nodes.push(OctreeNode::new(center + offset, half_size));
+        }
+
+        self.nodes[node].kind = OctreeNodeKind::Internal(first_child);
+    }
+
+    fn aggregate(&mut self, node: usize, positions: &[DVec3]) {
+        let (mass, weighted) = match &self.nodes[node].kind {
+            OctreeNodeKind::Empty => (0.0, DVec3::ZERO),
+            OctreeNodeKind::Leaf(bodies) => bodies.iter().fold((0.0, DVec3::ZERO), |(m, w), b| {
+                (m + self.masses[*b], w + positions[*b] * self.masses[*b])
+            }),
+            &OctreeNodeKind::Internal(first_child) => {
+                (first_child..first_child + 8).fold((0.0, DVec3::ZERO), |(m, w), child| {
+                    self.aggregate(child, positions);
+
+                    let child = &self.nodes[child];
+                    (m + child.mass, w + child.mass_center * child.mass)
+                })
+            }
+        };
+
+        let node = &mut self.nodes[node];
+        node.mass = mass;
+        node.mass_center = if mass > 0.0 {
+            weighted / mass
+        } else {
+            node.center
+        };
+    }
+
+    pub fn accelerations(
+        &self,
+        g: f64,
+        theta: f64,
+        positions: &[DVec3],
+        correction: impl Fn(usize) -> DVec3 + Sync,
+    ) -> Vec<DVec3> {
+        positions
+            .par_iter()
+            .enumerate()
+            .map(|(i, p)| self.acceleration(g, theta, i, *p, positions) + correction(i))
+            .collect()
+    }
+
+    fn acceleration(
+        &self,
+        g: f64,
+        theta: f64,
+        body: usize,
+        position: DVec3,
+        positions: &[DVec3],
+    ) -> DVec3 {
+        let mut a = DVec3::ZERO;
+        let mut stack = vec![0];
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            if node.mass == 0.0 {
+                continue;
+            }
+
+            match &node.kind {
+                OctreeNodeKind::Empty => {}
+                OctreeNodeKind::Leaf(bodies) => {
+                    for &b in bodies.iter().filter(|b| **b != body) {
+                        a += attraction(g, self.masses[b], positions[b] - position);
+                    }
+                }
+                &OctreeNodeKind::Internal(first_child) => {
+                    let r = node.mass_center - position;
+
+                    if !node.contains(position) && 2.0 * node.half_size < theta * r.length() {
+                        a += attraction(g, node.mass, r);
+                    } else {
+                        stack.extend(first_child..first_child + 8);
+                    }
+                }
+            }
+        }
+
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{direct_accelerations, Octree};
+
+    // cluster of massive bodies with a few test particles among them
+    fn cluster(count: usize) -> (Vec<f64>, Vec<DVec3>, Vec<usize>) {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let masses = (0..count)
+            .map(|i| {
+                if i % 10 == 0 {
+                    0.0
+                } else {
+                    rng.gen_range(1.0..10.0)
+                }
+            })
+            .collect::<Vec<f64>>();
+        let positions = (0..count)
+            .map(|_| DVec3::new(rng.gen(), rng.gen(), rng.gen()) * 1e3)
+            .collect();
+        let sources = (0..count).filter(|i| masses[*i] > 0.0).collect();
+
+        (masses, positions, sources)
+    }
+
+    // largest error of octree accelerations relative to the direct ones
+    fn octree_error(theta: f64) -> f64 {
+        let (masses, positions, sources) = cluster(1000);
+
+        let direct = direct_accelerations(1.0, &masses, &positions, &sources, |_| DVec3::ZERO);
+        let octree = Octree::new(&masses, &positions, &sources).accelerations(
+            1.0,
+            theta,
+            &positions,
+            |_| DVec3::ZERO,
+        );
+
+        direct
+            .iter()
+            .zip(&octree)
+            .map(|(direct, octree)| direct.distance(*octree) / direct.length())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn octree_matches_direct_summation() {
+        // no node is far enough to be approximated
+        assert!(octree_error(0.0) < 1e-12);
+
+        // error shrinks with the opening angle
+        let (coarse, fine) = (octree_error(1.0), octree_error(0.3));
+
+        assert!(fine < 1e-2, "{fine}");
+        assert!(fine < coarse, "{fine} then {coarse}");
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+use super::{BodiesCollided, BodyId, SpaceBodies, SpaceSimulation, SpaceSimulationParams};
+
+pub struct SeekSpaceSimulationRequest {
+    pub time: DateTime<Utc>,
+}
+
+pub struct SpaceSimulationSeeked {
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Resource)]
+pub struct SpaceSimulationHistory {
+    // simulated seconds between checkpoints
+    pub interval: f64,
+    pub capacity: usize,
+    // bounds memory when there are thousands of test particles
+    pub max_stored_bodies: usize,
+    checkpoints: BTreeMap<DateTime<Utc>, SpaceBodies>,
+    body_ids: Vec<BodyId>,
+}
+
+impl Default for SpaceSimulationHistory {
+    fn default() -> Self {
+        Self {
+            interval: 86400.0,
+            capacity: 1024,
+            max_stored_bodies: 1_000_000,
+            checkpoints: default(),
+            body_ids: vec![],
+        }
+    }
+}
+
+impl SpaceSimulationHistory {
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    pub fn checkpoints(&self) -> impl DoubleEndedIterator<Item = (&DateTime<Utc>, &SpaceBodies)> {
+        self.checkpoints.iter()
+    }
+
+    pub fn range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let (first, _) = self.checkpoints.first_key_value()?;
+        let (last, _) = self.checkpoints.last_key_value()?;
+
+        Some((*first, *last))
+    }
+
+    pub fn nearest(&self, time: DateTime<Utc>) -> Option<(DateTime<Utc>, &SpaceBodies)> {
+        let before = self.checkpoints.range(..=time).next_back();
+        let after = self.checkpoints.range(time..).next();
+
+        let (time, bodies) = match (before, after) {
+            (Some(before), Some(after)) => {
+                if time - *before.0 <= *after.0 - time {
+                    before
+                } else {
+                    after
+                }
+            }
+            (before, after) => before.or(after)?,
+        };
+
+        Some((*time, bodies))
+    }
+
+    pub fn record(&mut self, simulation: &SpaceSimulation) {
+        // checkpoints can not restore bodies that were merged, inserted or removed since
+        if self.body_ids != *simulation.bodies.ids() {
+            self.body_ids = simulation.bodies.ids().clone();
+            self.clear();
+        }
+
+        let interval = Duration::milliseconds((self.interval * 1000.0) as i64);
+
+        let is_recorded = matches!(
+            self.nearest(simulation.time),
+            Some((time, _)) if (time - simulation.time).abs() < interval
+        );
+
+        if is_recorded {
+            return;
+        }
+
+        self.checkpoints
+            .insert(simulation.time, simulation.bodies.clone());
+
+        // time may run both ways, so the checkpoint farthest from now is dropped
+        let capacity = self
+            .capacity
+            .min(self.max_stored_bodies / simulation.bodies.len().max(1))
+            .max(1);
+
+        while self.checkpoints.len() > capacity {
+            let (first, _) = self.checkpoints.first_key_value().unwrap();
+            let (last, _) = self.checkpoints.last_key_value().unwrap();
+
+            if simulation.time - *first > *last - simulation.time {
+                self.checkpoints.pop_first();
+            } else {
+                self.checkpoints.pop_last();
+            }
+        }
+    }
+
+    // restores the checkpoint preceding the `time` in the playback direction and
+    // propagates from it, that reproduces the recorded steps exactly
+    pub fn seek(
+        &self,
+        simulation: &mut SpaceSimulation,
+        params: &SpaceSimulationParams,
+        time: DateTime<Utc>,
+    ) -> Option<Vec<BodiesCollided>> {
+        let before = self.checkpoints.range(..=time).next_back();
+        let after = self.checkpoints.range(time..).next();
+
+        let (checkpoint_time, bodies) = if params.speed < 0.0 {
+            after.or(before)?
+        } else {
+            before.or(after)?
+        };
+
+        simulation.time = *checkpoint_time;
+        simulation.bodies = bodies.clone();
+
+        Some(simulation.propagate_to(params, time))
+    }
+}
+
+pub mod systems {
+    use bevy::prelude::*;
+
+    use super::{SeekSpaceSimulationRequest, SpaceSimulationHistory, SpaceSimulationSeeked};
+    use crate::space::simulation::{BodiesCollided, SpaceSimulation, SpaceSimulationParams};
+
+    pub fn record_history(
+        mut history: ResMut<SpaceSimulationHistory>,
+        simulation: Res<SpaceSimulation>,
+    ) {
+        history.record(&simulation);
+    }
+
+    pub fn seek_simulation_on_request(
+        mut ev: EventReader<SeekSpaceSimulationRequest>,
+        history: Res<SpaceSimulationHistory>,
+        params: Res<SpaceSimulationParams>,
+        mut simulation: ResMut<SpaceSimulation>,
+        mut collisions: EventWriter<BodiesCollided>,
+        mut seeked: EventWriter<SpaceSimulationSeeked>,
+    ) {
+        // only the latest request matters while slider is dragged
+        let Some(request) = ev.iter().last() else { return };
+
+        let Some(collided) = history.seek(&mut simulation, &params, request.time) else { return };
+
+        collisions.send_batch(collided);
+        seeked.send(SpaceSimulationSeeked {
+            time: simulation.time,
+        });
+    }
+}
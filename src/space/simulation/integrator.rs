@@ -0,0 +1,456 @@
+use bevy::math::DVec3;
+use rayon::prelude::*;
+
+pub type AccelerationFn<'a> = dyn Fn(&[DVec3], &[DVec3]) -> Vec<DVec3> + Sync + 'a;
+
+pub trait Integrator: Send + Sync {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    );
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SpaceSimulationIntegrator {
+    #[default]
+    SemiImplicitEuler,
+    VelocityVerlet,
+    RungeKutta4,
+    Yoshida4,
+    DormandPrince45,
+}
+
+impl SpaceSimulationIntegrator {
+    pub const ALL: [SpaceSimulationIntegrator; 5] = [
+        SpaceSimulationIntegrator::SemiImplicitEuler,
+        SpaceSimulationIntegrator::VelocityVerlet,
+        SpaceSimulationIntegrator::RungeKutta4,
+        SpaceSimulationIntegrator::Yoshida4,
+        SpaceSimulationIntegrator::DormandPrince45,
+    ];
+
+    pub fn integrator(&self) -> &'static dyn Integrator {
+        match self {
+            SpaceSimulationIntegrator::SemiImplicitEuler => &SemiImplicitEuler,
+            SpaceSimulationIntegrator::VelocityVerlet => &VelocityVerlet,
+            SpaceSimulationIntegrator::RungeKutta4 => &RungeKutta4,
+            SpaceSimulationIntegrator::Yoshida4 => &Yoshida4,
+            SpaceSimulationIntegrator::DormandPrince45 => &DormandPrince45,
+        }
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, SpaceSimulationIntegrator::DormandPrince45)
+    }
+}
+
+impl std::str::FromStr for SpaceSimulationIntegrator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|integrator| format!("{integrator:?}").eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow::anyhow!("Unknown integrator: [{s}]"))
+    }
+}
+
+fn drift(positions: &mut [DVec3], velocities: &[DVec3], dt: f64) {
+    positions
+        .par_iter_mut()
+        .zip(velocities.par_iter())
+        .for_each(|(pos, vel)| *pos += *vel * dt);
+}
+
+fn kick(velocities: &mut [DVec3], accelerations: &[DVec3], dt: f64) {
+    velocities
+        .par_iter_mut()
+        .zip(accelerations.par_iter())
+        .for_each(|(vel, a)| *vel += *a * dt);
+}
+
+fn combine(
+    base: &[DVec3],
+    derivatives: &[Vec<DVec3>],
+    coefficients: &[f64],
+    dt: f64,
+) -> Vec<DVec3> {
+    base.par_iter()
+        .enumerate()
+        .map(|(i, x)| {
+            derivatives
+                .iter()
+                .zip(coefficients.iter())
+                .fold(*x, |x, (dx, c)| x + dx[i] * (c * dt))
+        })
+        .collect()
+}
+
+fn offset(base: &[DVec3], derivative: &[DVec3], dt: f64) -> Vec<DVec3> {
+    base.par_iter()
+        .zip(derivative.par_iter())
+        .map(|(x, dx)| *x + *dx * dt)
+        .collect()
+}
+
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    ) {
+        let a = acceleration(positions, velocities);
+        kick(velocities, &a, dt);
+        drift(positions, velocities, dt);
+    }
+}
+
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    ) {
+        let a = acceleration(positions, velocities);
+        kick(velocities, &a, dt / 2.0);
+        drift(positions, velocities, dt);
+
+        let a = acceleration(positions, velocities);
+        kick(velocities, &a, dt / 2.0);
+    }
+}
+
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    ) {
+        let k1v = acceleration(positions, velocities);
+
+        let x2 = offset(positions, velocities, dt / 2.0);
+        let v2 = offset(velocities, &k1v, dt / 2.0);
+        let k2v = acceleration(&x2, &v2);
+
+        let x3 = offset(positions, &v2, dt / 2.0);
+        let v3 = offset(velocities, &k2v, dt / 2.0);
+        let k3v = acceleration(&x3, &v3);
+
+        let x4 = offset(positions, &v3, dt);
+        let v4 = offset(velocities, &k3v, dt);
+        let k4v = acceleration(&x4, &v4);
+
+        positions.par_iter_mut().enumerate().for_each(|(i, pos)| {
+            *pos += (velocities[i] + 2.0 * v2[i] + 2.0 * v3[i] + v4[i]) * (dt / 6.0);
+        });
+
+        velocities.par_iter_mut().enumerate().for_each(|(i, vel)| {
+            *vel += (k1v[i] + 2.0 * k2v[i] + 2.0 * k3v[i] + k4v[i]) * (dt / 6.0);
+        });
+    }
+}
+
+pub struct Yoshida4;
+
+impl Yoshida4 {
+    const W1: f64 = 1.3512071919596578; // 1 / (2 - 2^(1/3))
+    const W0: f64 = -1.7024143839193153; // -2^(1/3) / (2 - 2^(1/3))
+
+    const C: [f64; 4] = [
+        Self::W1 / 2.0,
+        (Self::W0 + Self::W1) / 2.0,
+        (Self::W0 + Self::W1) / 2.0,
+        Self::W1 / 2.0,
+    ];
+    const D: [f64; 3] = [Self::W1, Self::W0, Self::W1];
+}
+
+impl Integrator for Yoshida4 {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    ) {
+        for (c, d) in Self::C.iter().zip(Self::D.iter()) {
+            drift(positions, velocities, c * dt);
+
+            let a = acceleration(positions, velocities);
+            kick(velocities, &a, d * dt);
+        }
+
+        drift(positions, velocities, Self::C[3] * dt);
+    }
+}
+
+pub struct DormandPrince45;
+
+impl DormandPrince45 {
+    const A: [&'static [f64]; 6] = [
+        &[1.0 / 5.0],
+        &[3.0 / 40.0, 9.0 / 40.0],
+        &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+        &[
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+        ],
+        &[
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+        ],
+        &[
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+        ],
+    ];
+
+    // difference between 5th and embedded 4th order weights
+    const E: [f64; 7] = [
+        71.0 / 57600.0,
+        0.0,
+        -71.0 / 16695.0,
+        71.0 / 1920.0,
+        -17253.0 / 339200.0,
+        22.0 / 525.0,
+        -1.0 / 40.0,
+    ];
+
+    // returns 5th order solution and its error relative to `tolerance`,
+    // step should be accepted when error is not greater than 1.
+    // error is scaled by state measured from `reference` (position, velocity),
+    // otherwise a body resting at the origin of the frame would need tiny steps
+    pub fn try_step(
+        &self,
+        positions: &[DVec3],
+        velocities: &[DVec3],
+        dt: f64,
+        tolerance: f64,
+        reference: (DVec3, DVec3),
+        acceleration: &AccelerationFn,
+    ) -> (Vec<DVec3>, Vec<DVec3>, f64) {
+        let mut kx = vec![velocities.to_vec()];
+        let mut kv = vec![acceleration(positions, velocities)];
+        let mut new_positions = Vec::new();
+
+        for a in Self::A {
+            let x = combine(positions, &kx, a, dt);
+            let v = combine(velocities, &kv, a, dt);
+
+            kv.push(acceleration(&x, &v));
+            kx.push(v);
+            new_positions = x;
+        }
+
+        // last stage is evaluated at the 5th order solution
+        let new_velocities = kx[6].clone();
+
+        let error_x = combine(&vec![DVec3::ZERO; positions.len()], &kx, &Self::E, dt);
+        let error_v = combine(&vec![DVec3::ZERO; velocities.len()], &kv, &Self::E, dt);
+
+        let error = (0..positions.len())
+            .into_par_iter()
+            .map(|i| {
+                let (x0, v0) = reference;
+                let scale_x = tolerance
+                    * (positions[i] - x0)
+                        .length()
+                        .max((new_positions[i] - x0).length())
+                    + f64::MIN_POSITIVE;
+                let scale_v = tolerance
+                    * (velocities[i] - v0)
+                        .length()
+                        .max((new_velocities[i] - v0).length())
+                    + f64::MIN_POSITIVE;
+
+                (error_x[i].length() / scale_x).max(error_v[i].length() / scale_v)
+            })
+            .reduce(|| 0.0, f64::max);
+
+        (new_positions, new_velocities, error)
+    }
+
+    pub fn next_step(dt: f64, error: f64) -> f64 {
+        if !error.is_finite() {
+            return dt * 0.2;
+        }
+
+        dt * (0.9 * error.powf(-0.2)).clamp(0.2, 5.0)
+    }
+}
+
+impl Integrator for DormandPrince45 {
+    fn integrate(
+        &self,
+        positions: &mut [DVec3],
+        velocities: &mut [DVec3],
+        dt: f64,
+        acceleration: &AccelerationFn,
+    ) {
+        let (new_positions, new_velocities, _) = self.try_step(
+            positions,
+            velocities,
+            dt,
+            f64::INFINITY,
+            (DVec3::ZERO, DVec3::ZERO),
+            acceleration,
+        );
+
+        positions.copy_from_slice(&new_positions);
+        velocities.copy_from_slice(&new_velocities);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+
+    use super::{DormandPrince45, SpaceSimulationIntegrator};
+
+    // unit harmonic oscillator, x = cos(t) and v = -sin(t)
+    fn oscillator(positions: &[DVec3], _: &[DVec3]) -> Vec<DVec3> {
+        positions.iter().map(|x| -*x).collect()
+    }
+
+    // orbit around unit gravitational parameter at the origin
+    fn kepler(positions: &[DVec3], _: &[DVec3]) -> Vec<DVec3> {
+        positions.iter().map(|x| -*x / x.length().powi(3)).collect()
+    }
+
+    fn error_at_one(integrator: SpaceSimulationIntegrator, steps: usize) -> f64 {
+        let (mut x, mut v) = ([DVec3::X], [DVec3::ZERO]);
+
+        for _ in 0..steps {
+            integrator
+                .integrator()
+                .integrate(&mut x, &mut v, 1.0 / steps as f64, &oscillator);
+        }
+
+        let exact = (DVec3::X * 1f64.cos(), DVec3::X * -(1f64.sin()));
+
+        x[0].distance(exact.0).max(v[0].distance(exact.1))
+    }
+
+    #[test]
+    fn integrators_converge_with_their_order() {
+        use SpaceSimulationIntegrator::*;
+
+        for (integrator, order) in [
+            (SemiImplicitEuler, 1.0),
+            (VelocityVerlet, 2.0),
+            (RungeKutta4, 4.0),
+            (Yoshida4, 4.0),
+            (DormandPrince45, 5.0),
+        ] {
+            // halving the step divides the error by 2^order
+            let measured = (error_at_one(integrator, 16) / error_at_one(integrator, 32)).log2();
+
+            assert!(
+                (measured - order).abs() < 0.3,
+                "{integrator:?}: order {measured}, expected {order}"
+            );
+        }
+    }
+
+    #[test]
+    fn symplectic_integrators_do_not_drift_in_energy() {
+        use SpaceSimulationIntegrator::*;
+
+        let energy = |x: DVec3, v: DVec3| v.length_squared() / 2.0 - 1.0 / x.length();
+        let initial = energy(DVec3::X, DVec3::Y * 1.1);
+
+        let steps_per_orbit = 64;
+        let dt = std::f64::consts::TAU / steps_per_orbit as f64;
+
+        // largest energy error over the first and the last ten of a hundred orbits
+        let errors = |integrator: SpaceSimulationIntegrator| {
+            let (mut x, mut v) = ([DVec3::X], [DVec3::Y * 1.1]);
+            let mut errors = vec![];
+
+            for _ in 0..100 {
+                let mut orbit: f64 = 0.0;
+
+                for _ in 0..steps_per_orbit {
+                    integrator
+                        .integrator()
+                        .integrate(&mut x, &mut v, dt, &kepler);
+                    orbit = orbit.max((energy(x[0], v[0]) - initial).abs());
+                }
+
+                errors.push(orbit);
+            }
+
+            let max = |errors: &[f64]| errors.iter().copied().fold(0.0, f64::max);
+
+            (max(&errors[..10]), max(&errors[90..]))
+        };
+
+        for integrator in [SemiImplicitEuler, VelocityVerlet, Yoshida4] {
+            let (first, last) = errors(integrator);
+
+            assert!(last < 1.5 * first, "{integrator:?}: {first} then {last}");
+        }
+
+        // not symplectic, energy is lost orbit after orbit
+        let (first, last) = errors(RungeKutta4);
+
+        assert!(last > 5.0 * first, "RungeKutta4: {first} then {last}");
+    }
+
+    #[test]
+    fn dormand_prince_error_estimate_follows_the_step() {
+        // circular motion of the oscillator, so error is relative to unit lengths
+        let (x, v) = ([DVec3::X], [DVec3::Y]);
+        let reference = (DVec3::ZERO, DVec3::ZERO);
+
+        let estimate = |dt: f64| {
+            let (x1, v1, error) = DormandPrince45.try_step(&x, &v, dt, 1.0, reference, &oscillator);
+
+            let actual = x1[0]
+                .distance(DVec3::X * dt.cos() + DVec3::Y * dt.sin())
+                .max(v1[0].distance(DVec3::Y * dt.cos() - DVec3::X * dt.sin()));
+
+            (error, actual)
+        };
+
+        let (error, actual) = estimate(0.2);
+        let (half_error, half_actual) = estimate(0.1);
+
+        // local error of the embedded 4th order solution grows as dt^5
+        assert!(
+            (error / half_error).log2() > 4.7 && (error / half_error).log2() < 5.3,
+            "{error} then {half_error}"
+        );
+
+        // 5th order solution that is taken is more accurate than the estimate
+        assert!(actual < error && half_actual < half_error);
+
+        // step grows while error is below tolerance and shrinks above it
+        assert!(DormandPrince45::next_step(1.0, 1e-3) > 1.0);
+        assert!(DormandPrince45::next_step(1.0, 1e3) < 1.0);
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::math::DVec3;
+
+use super::SpaceBodies;
+
+const BISECTION_ITERATIONS: usize = 64;
+
+// libration points L1 - L5 of a pair of bodies, in the frame rotating with the pair
+// as if it moved on a circular orbit at the current separation
+#[derive(Debug, Clone, Copy)]
+pub struct LagrangePoints {
+    pub positions: [DVec3; 5],
+    pub velocities: [DVec3; 5],
+}
+
+// root of the balance of gravity and centrifugal force along the line through the pair,
+// in units of separation from the barycenter, with the primary at -`mu` and the
+// secondary at 1 - `mu`, the balance grows through the root in all three intervals
+fn collinear_point(mu: f64, mut low: f64, mut high: f64) -> f64 {
+    let balance = |x: f64| {
+        let (to_primary, to_secondary) = (x + mu, x - 1.0 + mu);
+
+        x - (1.0 - mu) * to_primary / to_primary.abs().powi(3)
+            - mu * to_secondary / to_secondary.abs().powi(3)
+    };
+
+    for _ in 0..BISECTION_ITERATIONS {
+        let middle = (low + high) / 2.0;
+
+        if balance(middle) < 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+impl SpaceBodies {
+    // the heavier of bodies `i` and `j` is the primary, returns None if both are massless
+    // or they do not move relative to each other
+    pub fn lagrange_points(&self, i: usize, j: usize) -> Option<LagrangePoints> {
+        let masses = self.masses();
+
+        let (primary, secondary) = if masses[i] >= masses[j] {
+            (i, j)
+        } else {
+            (j, i)
+        };
+
+        let total_mass = masses[primary] + masses[secondary];
+
+        let separation = self.positions()[secondary] - self.positions()[primary];
+        let relative_velocity = self.velocities()[secondary] - self.velocities()[primary];
+        let angular_momentum = separation.cross(relative_velocity);
+
+        if total_mass <= 0.0 || angular_momentum.length_squared() == 0.0 {
+            return None;
+        }
+
+        let mu = masses[secondary] / total_mass;
+        let distance = separation.length();
+
+        let barycenter = self.positions()[primary] + separation * mu;
+        let barycenter_velocity = self.velocities()[primary] + relative_velocity * mu;
+
+        let axis = separation / distance;
+        // L4 leads the secondary on its orbit
+        let leading = angular_momentum.normalize().cross(axis);
+        let angular_velocity = angular_momentum / distance.powi(2);
+
+        let collinear =
+            |low: f64, high: f64| barycenter + axis * distance * collinear_point(mu, low, high);
+
+        let triangular = |side: f64| {
+            self.positions()[primary] + (axis * 0.5 + leading * side * 3f64.sqrt() / 2.0) * distance
+        };
+
+        let positions = [
+            collinear(-mu, 1.0 - mu),
+            collinear(1.0 - mu, 2.0),
+            collinear(-2.0, -mu),
+            triangular(1.0),
+            triangular(-1.0),
+        ];
+
+        let velocities = positions
+            .map(|position| barycenter_velocity + angular_velocity.cross(position - barycenter));
+
+        Some(LagrangePoints {
+            positions,
+            velocities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+
+    use crate::space::simulation::{SpaceBodies, SpaceBody};
+
+    // Earth - Moon mass ratio
+    const MU: f64 = 0.012150585;
+
+    // primary at the origin, secondary on the circular orbit at unit distance
+    // with unit angular velocity, as G = 1 and total mass is 1
+    fn earth_moon() -> SpaceBodies {
+        let mut bodies = SpaceBodies::default();
+
+        bodies
+            .insert(
+                "earth".into(),
+                SpaceBody {
+                    mass: 1.0 - MU,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        bodies
+            .insert(
+                "moon".into(),
+                SpaceBody {
+                    position: DVec3::X,
+                    velocity: DVec3::Z,
+                    mass: MU,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        bodies
+    }
+
+    #[test]
+    fn points_are_at_equilibrium_in_rotating_frame() {
+        let bodies = earth_moon();
+        let points = bodies.lagrange_points(1, 0).unwrap();
+
+        let barycenter = DVec3::X * MU;
+
+        for (k, position) in points.positions.iter().enumerate() {
+            let gravity = |center: DVec3, mass: f64| {
+                let r = center - *position;
+                r * mass / r.length().powi(3)
+            };
+
+            // centrifugal acceleration cancels gravity of both bodies
+            let balance =
+                gravity(DVec3::ZERO, 1.0 - MU) + gravity(DVec3::X, MU) + (*position - barycenter);
+
+            assert!(balance.length() < 1e-12, "L{}: {balance}", k + 1);
+
+            // points corotate with the pair, its angular velocity is along -y
+            let velocity = DVec3::Z * MU + DVec3::NEG_Y.cross(*position - barycenter);
+
+            assert!(
+                points.velocities[k].distance(velocity) < 1e-12,
+                "L{}",
+                k + 1
+            );
+        }
+    }
+
+    #[test]
+    fn points_match_known_positions() {
+        let points = earth_moon().lagrange_points(0, 1).unwrap().positions;
+
+        // collinear points relative to the barycenter, in units of separation
+        for (k, expected) in [0.836915, 1.155682, -1.005063].into_iter().enumerate() {
+            let x = points[k].x - MU;
+
+            assert!((x - expected).abs() < 1e-6, "L{}: {x}", k + 1);
+            assert!(points[k].y.abs() < 1e-12 && points[k].z.abs() < 1e-12);
+        }
+
+        // triangular points are 60 degrees from the secondary, L4 ahead of it
+        for (k, side) in [(3, 1.0), (4, -1.0)] {
+            let position = points[k];
+
+            assert!((position.length() - 1.0).abs() < 1e-12);
+            assert!((position.distance(DVec3::X) - 1.0).abs() < 1e-12);
+
+            let angle = position.angle_between(DVec3::X).to_degrees();
+
+            assert!((angle - 60.0).abs() < 1e-9);
+            assert!(position.z * side > 0.0, "L{}: {position}", k + 1);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use bevy::math::{DMat3, DVec3};
+use chrono::{DateTime, Duration, Utc};
+
+use super::{BodyId, SpaceBodies};
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SpaceBodyManeuverKind {
+    // instant change of velocity, m/s
+    Impulse { delta_v: DVec3 },
+    // constant acceleration for `duration` seconds, m/s^2, mass flow is neglected
+    Burn { acceleration: DVec3, duration: f64 },
+}
+
+// vectors are prograde, normal and radial components of the motion relative to
+// the `reference` body, given by name in scenario files and by handle in the simulation
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpaceBodyManeuver<R = BodyId> {
+    pub epoch: DateTime<Utc>,
+    pub reference: R,
+    pub kind: SpaceBodyManeuverKind,
+}
+
+impl<R> SpaceBodyManeuver<R> {
+    pub fn end(&self) -> DateTime<Utc> {
+        match self.kind {
+            SpaceBodyManeuverKind::Impulse { .. } => self.epoch,
+            SpaceBodyManeuverKind::Burn { duration, .. } => {
+                self.epoch + Duration::milliseconds((duration * 1000.0) as i64)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpaceBodySpacecraft<R = BodyId> {
+    #[serde(default)]
+    pub maneuvers: Vec<SpaceBodyManeuver<R>>,
+}
+
+impl<R> Default for SpaceBodySpacecraft<R> {
+    fn default() -> Self {
+        Self { maneuvers: vec![] }
+    }
+}
+
+// burn of the spacecraft `body` that lasts for the whole integration interval,
+// bodies are kept as handles, since merges between substeps move them to other indices
+#[derive(Debug, Clone, Copy)]
+pub struct ActiveBurn {
+    pub body: BodyId,
+    pub reference: BodyId,
+    pub acceleration: DVec3,
+}
+
+// active burn with bodies resolved to indices of the current state
+#[derive(Debug, Clone, Copy)]
+pub struct BurnForce {
+    pub body: usize,
+    pub reference: usize,
+    pub acceleration: DVec3,
+}
+
+impl BurnForce {
+    pub fn acceleration(&self, positions: &[DVec3], velocities: &[DVec3]) -> DVec3 {
+        maneuver_direction(
+            positions[self.body] - positions[self.reference],
+            velocities[self.body] - velocities[self.reference],
+            self.acceleration,
+        )
+    }
+}
+
+// converts prograde, normal and radial components into the simulation frame
+pub fn maneuver_direction(r: DVec3, v: DVec3, components: DVec3) -> DVec3 {
+    let prograde = v.normalize_or_zero();
+
+    // simulation frame is mirrored relative to the ecliptic one,
+    // so angular momentum of the orbit points along v x r here
+    let normal = v.cross(r).normalize_or_zero();
+    let radial = normal.cross(prograde);
+
+    DMat3::from_cols(prograde, normal, radial) * components
+}
+
+impl SpaceBodies {
+    // maneuvers with known reference body, as (spacecraft, reference, maneuver)
+    fn maneuvers(&self) -> impl Iterator<Item = (usize, usize, &SpaceBodyManeuver)> {
+        self.spacecraft()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, spacecraft)| Some((i, spacecraft.as_ref()?)))
+            .flat_map(|(i, spacecraft)| spacecraft.maneuvers.iter().map(move |m| (i, m)))
+            .filter_map(|(i, maneuver)| {
+                let reference = self.index(maneuver.reference)?;
+                (reference != i).then_some((i, reference, maneuver))
+            })
+    }
+
+    // times between `start` and `end` where maneuvers begin or end, ordered from `start`
+    // to `end`, which may be in the past, state at the epoch of an impulse is the one
+    // before it, so the earlier end of the interval is included and the later one is not
+    pub fn maneuver_boundaries(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let (from, to) = (start.min(end), start.max(end));
+
+        let mut boundaries = self
+            .maneuvers()
+            .flat_map(|(_, _, maneuver)| [maneuver.epoch, maneuver.end()])
+            .filter(|time| from <= *time && *time < to)
+            .collect::<Vec<_>>();
+
+        boundaries.sort();
+        boundaries.dedup();
+
+        if end < start {
+            boundaries.reverse();
+        }
+
+        boundaries
+    }
+
+    // burns active between `from` and `to`, interval must not contain maneuver boundaries
+    pub fn active_burns(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<ActiveBurn> {
+        let middle = from + (to - from) / 2;
+
+        self.maneuvers()
+            .filter_map(|(body, reference, maneuver)| match maneuver.kind {
+                SpaceBodyManeuverKind::Burn { acceleration, .. }
+                    if maneuver.epoch <= middle && middle < maneuver.end() =>
+                {
+                    Some(ActiveBurn {
+                        body: self.ids()[body],
+                        reference: self.ids()[reference],
+                        acceleration,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // burns of bodies that still exist, at their current indices
+    pub fn burn_forces(&self, burns: &[ActiveBurn]) -> Vec<BurnForce> {
+        burns
+            .iter()
+            .filter_map(|burn| {
+                Some(BurnForce {
+                    body: self.index(burn.body)?,
+                    reference: self.index(burn.reference)?,
+                    acceleration: burn.acceleration,
+                })
+            })
+            .collect()
+    }
+
+    // applies impulses scheduled at `time`, or reverts them when going backward in time
+    pub fn apply_impulses(&mut self, time: DateTime<Utc>, backward: bool) {
+        let impulses = self
+            .maneuvers()
+            .filter_map(|(body, reference, maneuver)| match maneuver.kind {
+                SpaceBodyManeuverKind::Impulse { delta_v } if maneuver.epoch == time => {
+                    let r = self.positions()[body] - self.positions()[reference];
+                    let v = self.velocities()[body] - self.velocities()[reference];
+
+                    Some((body, maneuver_direction(r, v, delta_v)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (body, delta_v) in impulses {
+            if backward {
+                self.velocities_mut()[body] -= delta_v;
+            } else {
+                self.velocities_mut()[body] += delta_v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+    use chrono::{TimeZone, Utc};
+
+    use super::{SpaceBodyManeuver, SpaceBodyManeuverKind, SpaceBodySpacecraft};
+    use crate::space::simulation::{SpaceBody, SpaceSimulation, SpaceSimulationParams};
+
+    #[test]
+    fn burn_follows_body_moved_by_merge() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        // thrust is the only force
+        let mut simulation = SpaceSimulation {
+            time: start,
+            G: 0.0,
+            ..Default::default()
+        };
+
+        let spacecraft = simulation
+            .bodies
+            .insert(
+                "spacecraft".into(),
+                SpaceBody {
+                    position: DVec3::new(1e6, 0.0, 0.0),
+                    velocity: DVec3::new(0.0, 0.0, 1000.0),
+                    mass: 1.0,
+                    radius: 1.0,
+                    spacecraft: Some(SpaceBodySpacecraft {
+                        maneuvers: vec![SpaceBodyManeuver {
+                            epoch: start,
+                            reference: "planet".into(),
+                            kind: SpaceBodyManeuverKind::Burn {
+                                acceleration: DVec3::new(1.0, 0.0, 0.0),
+                                duration: 1000.0,
+                            },
+                        }],
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // absorbed by the planet after the first substep, the planet takes its index
+        simulation
+            .bodies
+            .insert(
+                "moonlet".into(),
+                SpaceBody {
+                    position: DVec3::new(0.0, 0.0, 5.0),
+                    mass: 1.0,
+                    radius: 1.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let planet = simulation
+            .bodies
+            .insert(
+                "planet".into(),
+                SpaceBody {
+                    mass: 1e10,
+                    radius: 10.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let params = SpaceSimulationParams {
+            percision: 4,
+            ..Default::default()
+        };
+
+        let collisions = simulation.take_step_smooth(&params, 100.0);
+
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(simulation.bodies.len(), 2);
+
+        let velocity = |id| simulation.bodies.velocities()[simulation.bodies.index(id).unwrap()];
+
+        // whole step of prograde thrust lands on the spacecraft
+        assert!(velocity(spacecraft).distance(DVec3::new(0.0, 0.0, 1100.0)) < 1e-9);
+        assert!(velocity(planet).length() < 1e-9);
+    }
+
+    #[test]
+    fn impulse_survives_stepping_back_and_forth() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let epoch = start + chrono::Duration::hours(2);
+
+        let mut simulation = SpaceSimulation {
+            time: start,
+            G: 0.0,
+            ..Default::default()
+        };
+
+        simulation
+            .bodies
+            .insert(
+                "planet".into(),
+                SpaceBody {
+                    mass: 1e10,
+                    radius: 10.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let spacecraft = simulation
+            .bodies
+            .insert(
+                "spacecraft".into(),
+                SpaceBody {
+                    position: DVec3::new(1e6, 0.0, 0.0),
+                    velocity: DVec3::new(0.0, 0.0, 1000.0),
+                    mass: 1.0,
+                    radius: 1.0,
+                    spacecraft: Some(SpaceBodySpacecraft {
+                        maneuvers: vec![SpaceBodyManeuver {
+                            epoch,
+                            reference: "planet".into(),
+                            kind: SpaceBodyManeuverKind::Impulse {
+                                delta_v: DVec3::new(10.0, 0.0, 0.0),
+                            },
+                        }],
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let params = SpaceSimulationParams {
+            step: 3600.0,
+            ..Default::default()
+        };
+
+        let velocity = |simulation: &SpaceSimulation| {
+            simulation.bodies.velocities()[simulation.bodies.index(spacecraft).unwrap()]
+        };
+
+        let before = DVec3::new(0.0, 0.0, 1000.0);
+        let after = DVec3::new(0.0, 0.0, 1010.0);
+
+        // steps end and start exactly at the epoch, in both directions
+        for (hours, expected) in [(3, after), (2, before), (3, after), (1, before), (3, after)] {
+            simulation.propagate_to(&params, start + chrono::Duration::hours(hours));
+
+            assert!(velocity(&simulation).distance(expected) < 1e-9);
+        }
+
+        // two hours before the impulse and one after it
+        let position = simulation.bodies.positions()[simulation.bodies.index(spacecraft).unwrap()];
+
+        assert!(position.distance(DVec3::new(1e6, 0.0, 1000.0 * 7200.0 + 1010.0 * 3600.0)) < 1e-3);
+    }
+}
@@ -1,11 +1,53 @@
 use bevy::prelude::*;
 
+pub mod collision;
+pub use collision::*;
+
+pub mod diagnostics;
+pub use diagnostics::*;
+
+pub mod events;
+pub use events::*;
+
+pub mod gravity;
+pub use gravity::*;
+
+pub mod history;
+pub use history::*;
+
+pub mod integrator;
+pub use integrator::*;
+
+pub mod lagrange_points;
+pub use lagrange_points::*;
+
+pub mod maneuvers;
+pub use maneuvers::*;
+
+pub mod non_gravitational;
+pub use non_gravitational::*;
+
+pub mod orbital_elements;
+pub use orbital_elements::*;
+
 pub mod params;
 pub use params::*;
 
+pub mod patched_conics;
+pub use patched_conics::*;
+
+pub mod prediction;
+pub use prediction::*;
+
 pub mod space_simulation;
 pub use space_simulation::*;
 
+pub mod test_particles;
+pub use test_particles::*;
+
+pub mod zonal_harmonics;
+pub use zonal_harmonics::*;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SpaceSimulationState {
     Running,
@@ -43,9 +85,38 @@ pub struct SpaceSimulationPlugin;
 impl Plugin for SpaceSimulationPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<systems::ToggleSpaceSimulationStateEvent>();
+        app.add_event::<BodiesCollided>();
+        app.add_event::<SeekSpaceSimulationRequest>();
+        app.add_event::<SpaceSimulationSeeked>();
+        app.add_event::<SpaceSimulationEventDetected>();
+        app.add_event::<SpaceBodiesEdited>();
+        app.init_resource::<SpaceSimulationDiagnostics>();
+        app.init_resource::<SpaceSimulationHistory>();
+        app.init_resource::<SpaceSimulationEvents>();
+        app.init_resource::<SpaceSimulationPrediction>();
         app.add_state(SpaceSimulationState::Stopped);
 
         app.add_system(systems::toggle_simulation_state);
+        app.add_system(
+            diagnostics::systems::update_diagnostics
+                .after(space_simulation::systems::simulation_take_step),
+        );
+        app.add_system(
+            history::systems::seek_simulation_on_request
+                .after(space_simulation::systems::simulation_take_step),
+        );
+        app.add_system(
+            events::systems::pause_on_events
+                .after(space_simulation::systems::simulation_take_step)
+                .before(history::systems::seek_simulation_on_request),
+        );
+        app.add_system(
+            history::systems::record_history.after(history::systems::seek_simulation_on_request),
+        );
+        app.add_system(
+            prediction::systems::update_prediction
+                .after(history::systems::seek_simulation_on_request),
+        );
 
         app.add_system_set(
             SystemSet::on_update(SpaceSimulationState::Running)
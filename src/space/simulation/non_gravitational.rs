@@ -0,0 +1,74 @@
+use bevy::math::DVec3;
+
+const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+const DAY: f64 = 86400.0;
+
+// radiation pressure of the Sun at 1 AU, N/m^2
+const SOLAR_RADIATION_PRESSURE: f64 = 4.56e-6;
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct SpaceBodyNonGravitational {
+    // Marsden comet parameters in AU/day^2, as horizons gives them
+    #[serde(default)]
+    pub a1: f64,
+    #[serde(default)]
+    pub a2: f64,
+    #[serde(default)]
+    pub a3: f64,
+    // solar radiation pressure, m^2/kg
+    #[serde(default)]
+    pub area_to_mass: f64,
+    #[serde(default = "default_reflectivity")]
+    pub reflectivity: f64,
+}
+
+fn default_reflectivity() -> f64 {
+    1.0
+}
+
+impl Default for SpaceBodyNonGravitational {
+    fn default() -> Self {
+        Self {
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            area_to_mass: 0.0,
+            reflectivity: default_reflectivity(),
+        }
+    }
+}
+
+impl SpaceBodyNonGravitational {
+    // `r` and `v` are relative to the Sun
+    pub fn acceleration(&self, r: DVec3, v: DVec3) -> DVec3 {
+        let distance = r.length();
+        let radial = r / distance;
+
+        let normal = r.cross(v).normalize_or_zero();
+        let transverse = normal.cross(radial);
+
+        let outgassing = (self.a1 * radial + self.a2 * transverse + self.a3 * normal)
+            * (Self::marsden_g(distance / ASTRONOMICAL_UNIT) * ASTRONOMICAL_UNIT / (DAY * DAY));
+
+        let radiation_pressure = radial
+            * (self.reflectivity
+                * SOLAR_RADIATION_PRESSURE
+                * self.area_to_mass
+                * (ASTRONOMICAL_UNIT / distance).powi(2));
+
+        outgassing + radiation_pressure
+    }
+
+    // water ice sublimation law, distance is in AU
+    fn marsden_g(distance: f64) -> f64 {
+        const ALPHA: f64 = 0.111262;
+        const R0: f64 = 2.808;
+        const M: f64 = 2.15;
+        const N: f64 = 5.093;
+        const K: f64 = 4.6142;
+
+        let x = distance / R0;
+
+        ALPHA * x.powf(-M) * (1.0 + x.powf(N)).powf(-K)
+    }
+}
@@ -0,0 +1,379 @@
+use std::f64::consts::{PI, TAU};
+
+use bevy::math::{DMat3, DVec3};
+use chrono::{DateTime, Utc};
+
+use super::{BodyId, SpaceBodies, SpaceBody};
+
+// below this eccentricity or inclination periapsis or node are undefined
+const DEGENERATE: f64 = 1e-11;
+
+// osculating elements of the two body orbit, angles are in radians relative
+// to the ecliptic and equinox, the frame horizons vectors are given in
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    // negative for unbound orbits
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub true_anomaly: f64,
+    pub mean_anomaly: f64,
+    // None for unbound orbits
+    pub period: Option<f64>,
+}
+
+// simulation frame is the ecliptic one with y and z swapped and negated,
+// conversion is its own inverse
+pub fn ecliptic_from_simulation(v: DVec3) -> DVec3 {
+    DVec3::new(v.x, -v.z, -v.y)
+}
+
+pub fn simulation_from_ecliptic(v: DVec3) -> DVec3 {
+    ecliptic_from_simulation(v)
+}
+
+impl OrbitalElements {
+    // `r` and `v` are relative to the central body, in the simulation frame,
+    // `gm` is the gravitational parameter of both bodies
+    pub fn from_state(gm: f64, r: DVec3, v: DVec3) -> Self {
+        let r = ecliptic_from_simulation(r);
+        let v = ecliptic_from_simulation(v);
+
+        let distance = r.length();
+
+        let h = r.cross(v);
+        let normal = h.normalize_or_zero();
+        let node = DVec3::Z.cross(h);
+        let eccentricity_vector = ((v.length_squared() - gm / distance) * r - r.dot(v) * v) / gm;
+
+        let eccentricity = eccentricity_vector.length();
+        let semi_major_axis = 1.0 / (2.0 / distance - v.length_squared() / gm);
+        let inclination = h.x.hypot(h.y).atan2(h.z);
+
+        // equatorial orbits are measured from the equinox direction
+        let node_direction = if node.length() > DEGENERATE * h.length() {
+            node.normalize()
+        } else {
+            DVec3::X
+        };
+
+        let angle = |from: DVec3, to: DVec3| {
+            normal
+                .dot(from.cross(to))
+                .atan2(from.dot(to))
+                .rem_euclid(TAU)
+        };
+
+        let longitude_of_ascending_node = node_direction.y.atan2(node_direction.x).rem_euclid(TAU);
+
+        // circular orbits are measured from the node
+        let (argument_of_periapsis, true_anomaly) = if eccentricity > DEGENERATE {
+            (
+                angle(node_direction, eccentricity_vector),
+                angle(eccentricity_vector, r),
+            )
+        } else {
+            (0.0, angle(node_direction, r))
+        };
+
+        let mean_anomaly = if eccentricity < 1.0 {
+            let eccentric_anomaly = ((1.0 - eccentricity.powi(2)).sqrt() * true_anomaly.sin())
+                .atan2(eccentricity + true_anomaly.cos());
+
+            (eccentric_anomaly - eccentricity * eccentric_anomaly.sin()).rem_euclid(TAU)
+        } else {
+            let hyperbolic_anomaly = 2.0
+                * (((eccentricity - 1.0) / (eccentricity + 1.0)).sqrt()
+                    * (true_anomaly / 2.0).tan())
+                .atanh();
+
+            eccentricity * hyperbolic_anomaly.sinh() - hyperbolic_anomaly
+        };
+
+        let period = (semi_major_axis > 0.0).then(|| TAU * (semi_major_axis.powi(3) / gm).sqrt());
+
+        Self {
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            longitude_of_ascending_node,
+            argument_of_periapsis,
+            true_anomaly,
+            mean_anomaly,
+            period,
+        }
+    }
+
+    // periapsis must be above the center, so unbound orbits need negative semi-major axis,
+    // parabolic orbits are not supported
+    pub fn is_valid(&self) -> bool {
+        self.eccentricity >= 0.0 && self.semi_major_axis * (1.0 - self.eccentricity) > 0.0
+    }
+
+    pub fn mean_motion(&self, gm: f64) -> f64 {
+        (gm / self.semi_major_axis.abs().powi(3)).sqrt()
+    }
+
+    // position and velocity relative to the central body in the simulation frame,
+    // position on the orbit is given by the mean anomaly, true anomaly is ignored
+    pub fn to_state(&self, gm: f64) -> (DVec3, DVec3) {
+        let e = self.eccentricity;
+        let true_anomaly = true_from_mean_anomaly(e, self.mean_anomaly);
+
+        let semi_latus_rectum = self.semi_major_axis * (1.0 - e * e);
+        let distance = semi_latus_rectum / (1.0 + e * true_anomaly.cos());
+        let speed = (gm / semi_latus_rectum).sqrt();
+
+        let position = DVec3::new(true_anomaly.cos(), true_anomaly.sin(), 0.0) * distance;
+        let velocity = DVec3::new(-true_anomaly.sin(), e + true_anomaly.cos(), 0.0) * speed;
+
+        let rotation = DMat3::from_rotation_z(self.longitude_of_ascending_node)
+            * DMat3::from_rotation_x(self.inclination)
+            * DMat3::from_rotation_z(self.argument_of_periapsis);
+
+        (
+            simulation_from_ecliptic(rotation * position),
+            simulation_from_ecliptic(rotation * velocity),
+        )
+    }
+}
+
+fn true_from_mean_anomaly(e: f64, mean_anomaly: f64) -> f64 {
+    const ITERATIONS: usize = 64;
+
+    if e < 1.0 {
+        let mean_anomaly = mean_anomaly.rem_euclid(TAU);
+        let mut eccentric_anomaly = if e < 0.8 { mean_anomaly } else { PI };
+
+        for _ in 0..ITERATIONS {
+            let delta = (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+                / (1.0 - e * eccentric_anomaly.cos());
+            eccentric_anomaly -= delta;
+
+            if delta.abs() < 1e-15 {
+                break;
+            }
+        }
+
+        2.0 * ((1.0 + e).sqrt() * (eccentric_anomaly / 2.0).sin())
+            .atan2((1.0 - e).sqrt() * (eccentric_anomaly / 2.0).cos())
+    } else {
+        let mut hyperbolic_anomaly = (mean_anomaly / e).asinh();
+
+        for _ in 0..ITERATIONS {
+            let delta = (e * hyperbolic_anomaly.sinh() - hyperbolic_anomaly - mean_anomaly)
+                / (e * hyperbolic_anomaly.cosh() - 1.0);
+            hyperbolic_anomaly -= delta;
+
+            if delta.abs() < 1e-15 * hyperbolic_anomaly.abs().max(1.0) {
+                break;
+            }
+        }
+
+        2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (hyperbolic_anomaly / 2.0).tanh()).atan()
+    }
+}
+
+// body on the orbit around the `parent` given by elements at the `epoch`
+#[derive(Debug, Clone)]
+pub struct KeplerianBody {
+    pub name: String,
+    pub parent: BodyId,
+    pub elements: OrbitalElements,
+    pub epoch: DateTime<Utc>,
+    pub mass: f64,
+    pub radius: f64,
+}
+
+impl KeplerianBody {
+    // state at the `time`, orbit is propagated from the epoch as a two body one,
+    // returns None if the parent is unknown or elements are invalid
+    pub fn generate(&self, bodies: &SpaceBodies, g: f64, time: DateTime<Utc>) -> Option<SpaceBody> {
+        let parent = bodies.index(self.parent)?;
+
+        if !self.elements.is_valid() {
+            return None;
+        }
+
+        let gm = g * (bodies.masses()[parent] + self.mass);
+
+        let elapsed = (time - self.epoch).num_milliseconds() as f64 / 1000.0;
+
+        let elements = OrbitalElements {
+            mean_anomaly: self.elements.mean_anomaly + self.elements.mean_motion(gm) * elapsed,
+            ..self.elements
+        };
+
+        let (position, velocity) = elements.to_state(gm);
+
+        Some(SpaceBody {
+            position: bodies.positions()[parent] + position,
+            velocity: bodies.velocities()[parent] + velocity,
+            mass: self.mass,
+            radius: self.radius,
+            ..Default::default()
+        })
+    }
+}
+
+impl SpaceBodies {
+    // massive bodies from the most massive one, with radiuses of their spheres relative
+    // to their own attractors, scaled by `ratio` of masses of the body and its attractor,
+    // the most massive body attracts everything
+    pub fn nested_spheres(&self, ratio: impl Fn(f64) -> f64) -> Vec<(usize, f64)> {
+        let masses = self.masses();
+        let positions = self.positions();
+
+        let mut order = (0..self.len())
+            .filter(|i| masses[*i] > 0.0)
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| masses[*b].total_cmp(&masses[*a]));
+
+        let mut spheres: Vec<(usize, f64)> = Vec::with_capacity(order.len());
+
+        for i in order {
+            let radius = match self.innermost_sphere(&spheres, i) {
+                Some(parent) => {
+                    positions[i].distance(positions[parent]) * ratio(masses[i] / masses[parent])
+                }
+                None => f64::INFINITY,
+            };
+
+            spheres.push((i, radius));
+        }
+
+        spheres
+    }
+
+    // heavier body with the smallest of `spheres` that contains the body `i`
+    pub fn innermost_sphere(&self, spheres: &[(usize, f64)], i: usize) -> Option<usize> {
+        let masses = self.masses();
+        let positions = self.positions();
+
+        spheres
+            .iter()
+            .filter(|(k, radius)| {
+                *k != i && masses[*k] > masses[i] && positions[*k].distance(positions[i]) < *radius
+            })
+            .min_by(|(_, r1), (_, r2)| r1.total_cmp(r2))
+            .map(|(k, _)| *k)
+    }
+
+    // heavier body with the smallest Hill sphere that contains the body `i`
+    pub fn dominant_attractor(&self, i: usize) -> Option<usize> {
+        let spheres = self.nested_spheres(|ratio| (ratio / 3.0).cbrt());
+
+        self.innermost_sphere(&spheres, i)
+    }
+
+    // elements of the body `i` around the `central` body, or around its dominant attractor
+    pub fn orbital_elements(
+        &self,
+        g: f64,
+        i: usize,
+        central: Option<usize>,
+    ) -> Option<(usize, OrbitalElements)> {
+        let central = central
+            .filter(|c| *c != i)
+            .or_else(|| self.dominant_attractor(i))?;
+
+        let elements = OrbitalElements::from_state(
+            g * (self.masses()[central] + self.masses()[i]),
+            self.positions()[i] - self.positions()[central],
+            self.velocities()[i] - self.velocities()[central],
+        );
+
+        Some((central, elements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use bevy::math::DVec3;
+
+    use super::{simulation_from_ecliptic, OrbitalElements};
+
+    // gravitational parameter of the Sun
+    const GM: f64 = 1.32712440018e20;
+    const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+
+    #[test]
+    fn elements_survive_round_trip_through_state() {
+        for elements in [
+            OrbitalElements {
+                semi_major_axis: 2.7 * ASTRONOMICAL_UNIT,
+                eccentricity: 0.3,
+                inclination: 0.4,
+                longitude_of_ascending_node: 1.2,
+                argument_of_periapsis: 2.5,
+                mean_anomaly: 4.0,
+                ..Default::default()
+            },
+            // unbound orbits have negative semi-major axis
+            OrbitalElements {
+                semi_major_axis: -0.5 * ASTRONOMICAL_UNIT,
+                eccentricity: 1.8,
+                inclination: 2.0,
+                longitude_of_ascending_node: 5.0,
+                argument_of_periapsis: 0.7,
+                mean_anomaly: -1.5,
+                ..Default::default()
+            },
+        ] {
+            let (r, v) = elements.to_state(GM);
+            let back = OrbitalElements::from_state(GM, r, v);
+
+            let close = |a: f64, b: f64| (a - b).abs() < 1e-9 * a.abs().max(1.0);
+
+            assert!(
+                close(back.semi_major_axis, elements.semi_major_axis),
+                "{back:?}"
+            );
+            assert!(close(back.eccentricity, elements.eccentricity), "{back:?}");
+            assert!(close(back.inclination, elements.inclination), "{back:?}");
+            assert!(
+                close(
+                    back.longitude_of_ascending_node,
+                    elements.longitude_of_ascending_node
+                ),
+                "{back:?}"
+            );
+            assert!(
+                close(back.argument_of_periapsis, elements.argument_of_periapsis),
+                "{back:?}"
+            );
+            assert!(close(back.mean_anomaly, elements.mean_anomaly), "{back:?}");
+
+            let (r2, v2) = back.to_state(GM);
+
+            assert!(r2.distance(r) < 1e-6 * r.length());
+            assert!(v2.distance(v) < 1e-9 * v.length());
+        }
+    }
+
+    #[test]
+    fn circular_orbit_in_ecliptic() {
+        let speed = (GM / ASTRONOMICAL_UNIT).sqrt();
+
+        // prograde, counterclockwise seen from the north of the ecliptic
+        let elements = OrbitalElements::from_state(
+            GM,
+            simulation_from_ecliptic(DVec3::X * ASTRONOMICAL_UNIT),
+            simulation_from_ecliptic(DVec3::Y * speed),
+        );
+
+        let period = TAU * (ASTRONOMICAL_UNIT.powi(3) / GM).sqrt();
+
+        assert!((elements.semi_major_axis / ASTRONOMICAL_UNIT - 1.0).abs() < 1e-12);
+        assert!(elements.eccentricity < 1e-12);
+        assert!(elements.inclination.abs() < 1e-12);
+        assert!((elements.period.unwrap() / period - 1.0).abs() < 1e-12);
+
+        // a year, as Earth orbit is nearly circular
+        assert!((period / 86400.0 - 365.25).abs() < 0.1);
+    }
+}
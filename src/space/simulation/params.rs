@@ -1,7 +1,42 @@
 use bevy::prelude::*;
 
-#[derive(Resource)]
+use super::{SpaceSimulationForceBackend, SpaceSimulationIntegrator, SpaceSimulationMode};
+
+#[derive(Resource, Clone)]
 pub struct SpaceSimulationParams {
+    pub mode: SpaceSimulationMode,
     pub speed: f64,
     pub percision: usize,
+    // fixed step in simulated seconds
+    pub step: f64,
+    pub max_steps_per_frame: usize,
+    pub integrator: SpaceSimulationIntegrator,
+    pub tolerance: f64,
+    pub force_backend: SpaceSimulationForceBackend,
+    pub barnes_hut_theta: f64,
+    pub post_newtonian: bool,
+    pub zonal_harmonics: bool,
+    // in reference radiuses of the oblate body
+    pub zonal_harmonics_distance: f64,
+    pub non_gravitational: bool,
+}
+
+impl Default for SpaceSimulationParams {
+    fn default() -> Self {
+        Self {
+            mode: SpaceSimulationMode::NBody,
+            speed: 86400.0 * 1.0,
+            percision: 4,
+            step: 3600.0,
+            max_steps_per_frame: 64,
+            integrator: SpaceSimulationIntegrator::SemiImplicitEuler,
+            tolerance: 1e-9,
+            force_backend: SpaceSimulationForceBackend::Direct,
+            barnes_hut_theta: 0.5,
+            post_newtonian: false,
+            zonal_harmonics: false,
+            zonal_harmonics_distance: 100.0,
+            non_gravitational: true,
+        }
+    }
 }
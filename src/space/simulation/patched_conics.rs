@@ -0,0 +1,382 @@
+use bevy::math::DVec3;
+use rayon::prelude::*;
+
+use super::{OrbitalElements, SpaceBodies};
+
+// shortest interval orbits are sampled at for sphere of influence crossings
+const MIN_SAMPLE: f64 = 1.0;
+
+// long jumps are sampled in no more than this many intervals, so they do not stall
+// the frame, crossings shorter than an interval may be missed then
+const MAX_SAMPLES: f64 = 1024.0;
+
+const BISECTION_ITERATIONS: usize = 48;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SpaceSimulationMode {
+    #[default]
+    NBody,
+    // every body follows a Kepler orbit around its parent,
+    // parents change when bodies cross spheres of influence
+    PatchedConics,
+}
+
+impl SpaceSimulationMode {
+    pub const ALL: [SpaceSimulationMode; 2] = [
+        SpaceSimulationMode::NBody,
+        SpaceSimulationMode::PatchedConics,
+    ];
+
+    // analytic modes are exact for any step
+    pub fn is_analytic(&self) -> bool {
+        matches!(self, SpaceSimulationMode::PatchedConics)
+    }
+}
+
+impl std::str::FromStr for SpaceSimulationMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| format!("{mode:?}").eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow::anyhow!("Unknown simulation mode: [{s}]"))
+    }
+}
+
+// position and velocity relative to the central body after `dt` on the two body orbit
+pub fn kepler_propagate(gm: f64, r: DVec3, v: DVec3, dt: f64) -> (DVec3, DVec3) {
+    let elements = OrbitalElements::from_state(gm, r, v);
+
+    OrbitalElements {
+        mean_anomaly: elements.mean_anomaly + elements.mean_motion(gm) * dt,
+        ..elements
+    }
+    .to_state(gm)
+}
+
+// conics of all bodies around their parents, as they are at the start of an interval
+struct Conics<'a> {
+    g: f64,
+    masses: &'a [f64],
+    positions: &'a [DVec3],
+    velocities: &'a [DVec3],
+    parents: Vec<Option<usize>>,
+    spheres: Vec<f64>,
+    // only massive bodies have spheres of influence
+    massive: Vec<usize>,
+}
+
+impl Conics<'_> {
+    // absolute state of the body `i` after `dt`
+    fn state(&self, i: usize, dt: f64) -> (DVec3, DVec3) {
+        // the root moves uniformly, its reflex motion is neglected
+        let Some(p) = self.parents[i] else {
+            return (
+                self.positions[i] + self.velocities[i] * dt,
+                self.velocities[i],
+            );
+        };
+
+        let (parent_position, parent_velocity) = self.state(p, dt);
+
+        let (r, v) = kepler_propagate(
+            self.g * (self.masses[p] + self.masses[i]),
+            self.positions[i] - self.positions[p],
+            self.velocities[i] - self.velocities[p],
+            dt,
+        );
+
+        (parent_position + r, parent_velocity + v)
+    }
+
+    // parent the body `i` switches to within `dt`, if it leaves the sphere of its parent
+    // or enters a sphere of a heavier body sharing the same parent
+    fn next_parent(&self, i: usize, dt: f64) -> Option<usize> {
+        let p = self.parents[i]?;
+        let (position, _) = self.state(i, dt);
+
+        let distance = |k: usize| self.state(k, dt).0.distance(position);
+
+        if self.spheres[p].is_finite() && distance(p) > self.spheres[p] {
+            return self.parents[p];
+        }
+
+        self.massive.iter().copied().find(|k| {
+            *k != i
+                && self.parents[*k] == Some(p)
+                && self.masses[*k] > self.masses[i]
+                && distance(*k) < self.spheres[*k]
+        })
+    }
+
+    // longest interval that can not skip a sphere of influence crossing of the body `i`,
+    // orbits entirely within the sphere of the parent and away from its other
+    // children are never sampled
+    fn sample(&self, i: usize, shells: &[Option<OrbitalShell>]) -> f64 {
+        let (Some(p), Some(shell)) = (self.parents[i], shells[i]) else { return f64::INFINITY };
+
+        let mut sample = f64::INFINITY;
+
+        if shell.apoapsis > self.spheres[p] {
+            sample = sample.min(self.spheres[p] / shell.max_speed);
+        }
+
+        for &k in &self.massive {
+            let Some(other) = shells[k] else { continue };
+
+            if k == i || self.parents[k] != Some(p) || self.masses[k] <= self.masses[i] {
+                continue;
+            }
+
+            let sphere = self.spheres[k];
+
+            if shell.periapsis < other.apoapsis + sphere
+                && other.periapsis - sphere < shell.apoapsis
+            {
+                sample = sample.min(sphere / (shell.max_speed + other.max_speed));
+            }
+        }
+
+        sample
+    }
+}
+
+// range of distances and speeds of the body on its orbit around the parent
+#[derive(Clone, Copy)]
+struct OrbitalShell {
+    periapsis: f64,
+    apoapsis: f64,
+    max_speed: f64,
+}
+
+impl SpaceBodies {
+    // parents of all bodies and radiuses of their Laplace spheres of influence,
+    // only massive bodies have spheres, the one of the root is infinite
+    fn spheres_of_influence(&self) -> (Vec<Option<usize>>, Vec<f64>, Vec<usize>) {
+        let spheres = self.nested_spheres(|ratio| ratio.powf(0.4));
+
+        let parents = (0..self.len())
+            .map(|i| self.innermost_sphere(&spheres, i))
+            .collect();
+
+        let mut radiuses = vec![0.0; self.len()];
+        for (i, radius) in &spheres {
+            radiuses[*i] = *radius;
+        }
+
+        let massive = spheres.into_iter().map(|(i, _)| i).collect();
+
+        (parents, radiuses, massive)
+    }
+
+    // moves bodies along their conics by `dt` seconds, backward if negative
+    pub fn propagate_patched_conics(&mut self, g: f64, dt: f64) {
+        let mut elapsed = 0.0;
+
+        while elapsed < dt.abs() {
+            let (parents, spheres, massive) = self.spheres_of_influence();
+
+            let conics = Conics {
+                g,
+                masses: self.masses(),
+                positions: self.positions(),
+                velocities: self.velocities(),
+                parents,
+                spheres,
+                massive,
+            };
+
+            let shells = (0..self.len())
+                .map(|i| {
+                    let p = conics.parents[i]?;
+                    let gm = g * (conics.masses[p] + conics.masses[i]);
+
+                    let elements = OrbitalElements::from_state(
+                        gm,
+                        conics.positions[i] - conics.positions[p],
+                        conics.velocities[i] - conics.velocities[p],
+                    );
+
+                    let e = elements.eccentricity;
+                    let periapsis = elements.semi_major_axis * (1.0 - e);
+
+                    Some(OrbitalShell {
+                        periapsis,
+                        apoapsis: if e < 1.0 {
+                            elements.semi_major_axis * (1.0 + e)
+                        } else {
+                            f64::INFINITY
+                        },
+                        max_speed: (gm * (1.0 + e) / periapsis).sqrt(),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let sample = (0..self.len())
+                .into_par_iter()
+                .map(|i| conics.sample(i, &shells))
+                .reduce(|| f64::INFINITY, f64::min);
+
+            let step = sample
+                .max(MIN_SAMPLE)
+                .max(dt.abs() / MAX_SAMPLES)
+                .min(dt.abs() - elapsed)
+                .copysign(dt);
+
+            // bodies are moved from the heaviest, so parents are moved before their children
+            let mut order = (0..self.len()).collect::<Vec<_>>();
+            order.sort_by(|a, b| conics.masses[*b].total_cmp(&conics.masses[*a]));
+
+            let mut states = vec![(DVec3::ZERO, DVec3::ZERO); self.len()];
+
+            for i in order {
+                let (p, r, v, dt) = match conics.next_parent(i, step) {
+                    Some(next) => {
+                        // bisect the moment of crossing, then follow the new conic
+                        let (mut before, mut after) = (0.0, step);
+
+                        for _ in 0..BISECTION_ITERATIONS {
+                            let middle = (before + after) / 2.0;
+
+                            if conics.next_parent(i, middle).is_some() {
+                                after = middle;
+                            } else {
+                                before = middle;
+                            }
+                        }
+
+                        let (position, velocity) = conics.state(i, after);
+                        let (next_position, next_velocity) = conics.state(next, after);
+
+                        (
+                            next,
+                            position - next_position,
+                            velocity - next_velocity,
+                            step - after,
+                        )
+                    }
+                    None => {
+                        let Some(p) = conics.parents[i] else {
+                            states[i] = conics.state(i, step);
+                            continue;
+                        };
+
+                        (
+                            p,
+                            conics.positions[i] - conics.positions[p],
+                            conics.velocities[i] - conics.velocities[p],
+                            step,
+                        )
+                    }
+                };
+
+                let (r, v) = kepler_propagate(g * (conics.masses[p] + conics.masses[i]), r, v, dt);
+
+                states[i] = (states[p].0 + r, states[p].1 + v);
+            }
+
+            for (i, (position, velocity)) in states.into_iter().enumerate() {
+                self.positions_mut()[i] = position;
+                self.velocities_mut()[i] = velocity;
+            }
+
+            elapsed += step.abs();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use bevy::math::DVec3;
+    use chrono::{TimeZone, Utc};
+
+    use super::{kepler_propagate, SpaceSimulationMode};
+    use crate::space::simulation::{
+        SpaceBody, SpaceSimulation, SpaceSimulationIntegrator, SpaceSimulationParams,
+    };
+
+    #[test]
+    fn circular_orbit_returns_after_period() {
+        let (gm, radius): (f64, f64) = (1.0, 1.0);
+        let (r, v) = (DVec3::X * radius, DVec3::Z * (gm / radius).sqrt());
+        let period = TAU * (radius.powi(3) / gm).sqrt();
+
+        let (half_r, half_v) = kepler_propagate(gm, r, v, period / 2.0);
+
+        assert!(half_r.distance(-r) < 1e-12);
+        assert!(half_v.distance(-v) < 1e-12);
+
+        // backward propagation goes around the other way
+        let (back_r, _) = kepler_propagate(gm, r, v, -period / 4.0);
+
+        assert!(back_r.distance(-DVec3::Z) < 1e-12);
+
+        let (full_r, full_v) = kepler_propagate(gm, r, v, period);
+
+        assert!(full_r.distance(r) < 1e-12);
+        assert!(full_v.distance(v) < 1e-12);
+    }
+
+    #[test]
+    fn both_modes_keep_two_body_period() {
+        let radius: f64 = 1e7;
+        let g = 1.0;
+        let mass = 1e12;
+        let speed = (g * mass / radius).sqrt();
+        let period = TAU * (radius.powi(3) / (g * mass)).sqrt();
+
+        for mode in SpaceSimulationMode::ALL {
+            let mut simulation = SpaceSimulation {
+                time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                G: g,
+                ..Default::default()
+            };
+
+            simulation
+                .bodies
+                .insert(
+                    "star".into(),
+                    SpaceBody {
+                        mass,
+                        radius: 1.0,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            simulation
+                .bodies
+                .insert(
+                    "planet".into(),
+                    SpaceBody {
+                        position: DVec3::X * radius,
+                        velocity: DVec3::Z * speed,
+                        radius: 1.0,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            let params = SpaceSimulationParams {
+                mode,
+                integrator: SpaceSimulationIntegrator::RungeKutta4,
+                // whole seconds, as simulation time advances in milliseconds
+                step: 200.0,
+                ..Default::default()
+            };
+
+            let target =
+                simulation.time + chrono::Duration::milliseconds((period * 1000.0).round() as i64);
+            simulation.propagate_to(&params, target);
+
+            let position = simulation.bodies.positions()[1];
+
+            assert!(
+                position.distance(DVec3::X * radius) < 1e-6 * radius,
+                "{mode:?}: {position}"
+            );
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::{math::DVec3, prelude::*, tasks::Task, utils::HashMap};
+use chrono::{DateTime, Duration, Utc};
+
+use super::{BodyId, SpaceSimulation, SpaceSimulationParams};
+
+// state of bodies was changed outside of the simulation step, e.g. edited or given a maneuver
+pub struct SpaceBodiesEdited;
+
+#[derive(Default)]
+pub struct PredictedTrajectories {
+    pub times: Vec<DateTime<Utc>>,
+    // bodies of the simulation the prediction started from
+    pub ids: Vec<BodyId>,
+    // positions at `times`, bodies absorbed on the way have shorter trajectories
+    pub positions: HashMap<BodyId, Vec<DVec3>>,
+}
+
+#[derive(Resource)]
+pub struct SpaceSimulationPrediction {
+    pub enabled: bool,
+    pub all_bodies: bool,
+    // simulated seconds ahead in the playback direction
+    pub horizon: f64,
+    pub samples: usize,
+    // bodies to record, kept up to date by the display
+    pub bodies: Vec<BodyId>,
+    pub trajectories: Option<PredictedTrajectories>,
+    outdated: bool,
+    task: Option<Task<PredictedTrajectories>>,
+}
+
+impl Default for SpaceSimulationPrediction {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            all_bodies: false,
+            horizon: 86400.0 * 365.0,
+            samples: 512,
+            bodies: vec![],
+            trajectories: None,
+            outdated: true,
+            task: None,
+        }
+    }
+}
+
+impl SpaceSimulationPrediction {
+    pub fn invalidate(&mut self) {
+        self.outdated = true;
+    }
+
+    pub fn is_computing(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+impl SpaceSimulation {
+    // propagates the simulation over `duration` seconds, recording positions of `bodies`
+    // at `samples` + 1 evenly spaced epochs including the current one
+    pub fn predict(
+        &mut self,
+        params: &SpaceSimulationParams,
+        duration: f64,
+        samples: usize,
+        bodies: &[BodyId],
+    ) -> PredictedTrajectories {
+        let start = self.time;
+
+        let mut prediction = PredictedTrajectories {
+            ids: self.bodies.ids().clone(),
+            ..default()
+        };
+
+        for k in 0..=samples {
+            let offset = duration * k as f64 / samples.max(1) as f64;
+            let target = start + Duration::milliseconds((offset * 1000.0) as i64);
+
+            self.propagate_to(params, target);
+
+            // time is out of range
+            if self.time != target {
+                break;
+            }
+
+            prediction.times.push(self.time);
+
+            for id in bodies {
+                let Some(index) = self.bodies.index(*id) else { continue };
+
+                prediction
+                    .positions
+                    .entry(*id)
+                    .or_default()
+                    .push(self.bodies.positions()[index]);
+            }
+        }
+
+        prediction
+    }
+}
+
+pub mod systems {
+    use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
+
+    use super::{SpaceBodiesEdited, SpaceSimulationPrediction};
+    use crate::space::simulation::{SpaceSimulation, SpaceSimulationParams, SpaceSimulationSeeked};
+
+    pub fn update_prediction(
+        mut prediction: ResMut<SpaceSimulationPrediction>,
+        mut edited: EventReader<SpaceBodiesEdited>,
+        mut seeked: EventReader<SpaceSimulationSeeked>,
+        simulation: Res<SpaceSimulation>,
+        params: Res<SpaceSimulationParams>,
+    ) {
+        use futures_lite::future;
+
+        if !prediction.enabled {
+            prediction.task = None;
+            prediction.trajectories = None;
+            prediction.outdated = true;
+            return;
+        }
+
+        if edited.iter().count() > 0 || seeked.iter().count() > 0 {
+            prediction.outdated = true;
+        }
+
+        let duration = prediction.horizon.copysign(params.speed);
+
+        // merges, insertions and removals, or the prediction is running out
+        let stale = match (&prediction.task, &prediction.trajectories) {
+            (None, Some(trajectories)) => {
+                let elapsed =
+                    (simulation.time - trajectories.times[0]).num_milliseconds() as f64 / 1000.0;
+
+                trajectories.ids != *simulation.bodies.ids()
+                    || elapsed * duration.signum() < 0.0
+                    || elapsed.abs() > prediction.horizon / 4.0
+            }
+            _ => false,
+        };
+
+        if stale {
+            prediction.outdated = true;
+        }
+
+        if let Some(task) = &mut prediction.task {
+            if let Some(trajectories) = future::block_on(future::poll_once(task)) {
+                prediction.task = None;
+                prediction.trajectories = Some(trajectories).filter(|t| !t.times.is_empty());
+            }
+        }
+
+        // pending computation is dropped, that cancels it
+        if prediction.outdated {
+            let mut copy = simulation.clone();
+            let params = params.clone();
+            let samples = prediction.samples;
+            let bodies = prediction.bodies.clone();
+
+            prediction.task = Some(
+                AsyncComputeTaskPool::get()
+                    .spawn(async move { copy.predict(&params, duration, samples, &bodies) }),
+            );
+            prediction.outdated = false;
+        }
+    }
+}
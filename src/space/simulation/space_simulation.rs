@@ -1,9 +1,10 @@
-use std::cell::UnsafeCell;
-
 use bevy::{math::DVec3, prelude::*};
 use chrono::{DateTime, Duration, Utc};
 
-use super::SpaceSimulationParams;
+use super::{
+    ActiveBurn, BodiesCollided, DormandPrince45, ForceModel, SpaceBodyManeuver,
+    SpaceBodyNonGravitational, SpaceBodySpacecraft, SpaceBodyZonalHarmonics, SpaceSimulationParams,
+};
 
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct SpaceBodyRotation {
@@ -46,45 +47,196 @@ pub struct SpaceBody {
     pub mass: f64,
     pub radius: f64,
     pub rotation: SpaceBodyRotation,
+    #[serde(default)]
+    pub zonal_harmonics: Option<SpaceBodyZonalHarmonics>,
+    #[serde(default)]
+    pub non_gravitational: Option<SpaceBodyNonGravitational>,
+    // maneuvers refer to other bodies by name until the body is inserted
+    #[serde(default)]
+    pub spacecraft: Option<SpaceBodySpacecraft<String>>,
+}
+
+// handle of a body that stays valid while the body exists, handles of removed bodies
+// never refer to bodies inserted later in their place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BodyId {
+    slot: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Default, Clone)]
+struct BodySlot {
+    generation: u32,
+    index: Option<usize>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct SpaceBodies {
     positions: Vec<DVec3>,
     velocities: Vec<DVec3>,
     masses: Vec<f64>,
     radiuses: Vec<f64>,
     rotations: Vec<SpaceBodyRotation>,
-    map: bevy::utils::HashMap<String, usize>,
+    zonal_harmonics: Vec<Option<SpaceBodyZonalHarmonics>>,
+    non_gravitational: Vec<Option<SpaceBodyNonGravitational>>,
+    spacecraft: Vec<Option<SpaceBodySpacecraft>>,
+    // maneuvers of inserted spacecraft that refer to bodies not inserted yet
+    pending_maneuvers: Vec<(BodyId, SpaceBodyManeuver<String>)>,
+    names: Vec<String>,
+    ids: Vec<BodyId>,
+    slots: Vec<BodySlot>,
+    free_slots: Vec<u32>,
+    map: bevy::utils::HashMap<String, BodyId>,
 }
 
 #[allow(dead_code)]
 impl SpaceBodies {
-    pub fn insert(&mut self, name: String, body: SpaceBody) {
-        self.map.insert(name, self.len());
+    // names identify bodies in scenario files and in the UI, so they must be unique
+    pub fn insert(&mut self, name: String, body: SpaceBody) -> anyhow::Result<BodyId> {
+        if self.map.contains_key(&name) {
+            anyhow::bail!("Body [{name}] already exists");
+        }
+
+        let slot = self.free_slots.pop().unwrap_or_else(|| {
+            self.slots.push(default());
+            self.slots.len() as u32 - 1
+        });
+
+        let id = BodyId {
+            slot,
+            generation: self.slots[slot as usize].generation,
+        };
+
+        self.slots[slot as usize].index = Some(self.len());
+        self.map.insert(name.clone(), id);
+        self.names.push(name);
+        self.ids.push(id);
         self.positions.push(body.position);
         self.velocities.push(body.velocity);
         self.masses.push(body.mass);
         self.radiuses.push(body.radius);
         self.rotations.push(body.rotation);
+        self.zonal_harmonics.push(body.zonal_harmonics);
+        self.non_gravitational.push(body.non_gravitational);
+        self.spacecraft.push(
+            body.spacecraft
+                .as_ref()
+                .map(|_| SpaceBodySpacecraft::default()),
+        );
+
+        if let Some(spacecraft) = body.spacecraft {
+            self.pending_maneuvers.extend(
+                spacecraft
+                    .maneuvers
+                    .into_iter()
+                    .map(|maneuver| (id, maneuver)),
+            );
+        }
+
+        self.resolve_pending_maneuvers();
+
+        Ok(id)
+    }
+
+    // references of maneuvers are resolved to handles once both bodies are inserted
+    fn resolve_pending_maneuvers(&mut self) {
+        for (id, maneuver) in std::mem::take(&mut self.pending_maneuvers) {
+            // spacecraft was removed
+            let Some(index) = self.index(id) else { continue };
+
+            let Some(reference) = self.find(&maneuver.reference) else {
+                self.pending_maneuvers.push((id, maneuver));
+                continue;
+            };
+
+            self.spacecraft[index]
+                .get_or_insert_with(default)
+                .maneuvers
+                .push(SpaceBodyManeuver {
+                    epoch: maneuver.epoch,
+                    reference,
+                    kind: maneuver.kind,
+                });
+        }
+    }
+
+    // body as it is inserted, maneuvers refer to other bodies by name again,
+    // so it can be saved, maneuvers relative to removed bodies are dropped
+    pub fn to_body(&self, id: BodyId) -> Option<SpaceBody> {
+        let index = self.index(id)?;
+
+        let spacecraft = self.spacecraft[index].as_ref().map(|spacecraft| {
+            let resolved = spacecraft.maneuvers.iter().filter_map(|maneuver| {
+                Some(SpaceBodyManeuver {
+                    epoch: maneuver.epoch,
+                    reference: self.name(maneuver.reference)?.clone(),
+                    kind: maneuver.kind,
+                })
+            });
+
+            let pending = self
+                .pending_maneuvers
+                .iter()
+                .filter(|(spacecraft, _)| *spacecraft == id)
+                .map(|(_, maneuver)| maneuver.clone());
+
+            SpaceBodySpacecraft {
+                maneuvers: resolved.chain(pending).collect(),
+            }
+        });
+
+        Some(SpaceBody {
+            position: self.positions[index],
+            velocity: self.velocities[index],
+            mass: self.masses[index],
+            radius: self.radiuses[index],
+            rotation: self.rotations[index].clone(),
+            zonal_harmonics: self.zonal_harmonics[index],
+            non_gravitational: self.non_gravitational[index],
+            spacecraft,
+        })
     }
 
-    pub fn remove(&mut self, name: impl AsRef<str>) -> Option<usize> {
-        let Some(index) = self.map.remove(name.as_ref()) else { return None };
+    // returns the index the body had, the last body is moved there
+    pub fn remove(&mut self, id: BodyId) -> Option<usize> {
+        let index = self.index(id)?;
 
         self.positions.swap_remove(index);
         self.velocities.swap_remove(index);
         self.masses.swap_remove(index);
         self.radiuses.swap_remove(index);
         self.rotations.swap_remove(index);
+        self.zonal_harmonics.swap_remove(index);
+        self.non_gravitational.swap_remove(index);
+        self.spacecraft.swap_remove(index);
+        self.ids.swap_remove(index);
+
+        let name = self.names.swap_remove(index);
+
+        if self.map.get(&name) == Some(&id) {
+            self.map.remove(&name);
+        }
 
-        if let Some(swapped) = self.map.values_mut().max() {
-            *swapped = index;
+        if let Some(moved) = self.ids.get(index) {
+            self.slots[moved.slot as usize].index = Some(index);
         }
 
+        let slot = &mut self.slots[id.slot as usize];
+        slot.index = None;
+        slot.generation += 1;
+        self.free_slots.push(id.slot);
+
         Some(index)
     }
 
+    pub fn names(&self) -> &Vec<String> {
+        &self.names
+    }
+
+    pub fn ids(&self) -> &Vec<BodyId> {
+        &self.ids
+    }
+
     pub fn positions(&self) -> &Vec<DVec3> {
         &self.positions
     }
@@ -125,21 +277,85 @@ impl SpaceBodies {
         &mut self.rotations
     }
 
-    pub fn get_index(&self, name: impl AsRef<str>) -> usize {
-        self.map[name.as_ref()]
+    pub fn zonal_harmonics(&self) -> &Vec<Option<SpaceBodyZonalHarmonics>> {
+        &self.zonal_harmonics
+    }
+
+    pub fn zonal_harmonics_mut(&mut self) -> &mut Vec<Option<SpaceBodyZonalHarmonics>> {
+        &mut self.zonal_harmonics
+    }
+
+    pub fn non_gravitational(&self) -> &Vec<Option<SpaceBodyNonGravitational>> {
+        &self.non_gravitational
+    }
+
+    pub fn non_gravitational_mut(&mut self) -> &mut Vec<Option<SpaceBodyNonGravitational>> {
+        &mut self.non_gravitational
+    }
+
+    pub fn spacecraft(&self) -> &Vec<Option<SpaceBodySpacecraft>> {
+        &self.spacecraft
+    }
+
+    pub fn spacecraft_mut(&mut self) -> &mut Vec<Option<SpaceBodySpacecraft>> {
+        &mut self.spacecraft
+    }
+
+    // None if the body was removed
+    pub fn index(&self, id: BodyId) -> Option<usize> {
+        self.slots
+            .get(id.slot as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.index)
+    }
+
+    pub fn find(&self, name: impl AsRef<str>) -> Option<BodyId> {
+        self.map.get(name.as_ref()).copied()
+    }
+
+    pub fn name(&self, id: BodyId) -> Option<&String> {
+        Some(&self.names[self.index(id)?])
+    }
+
+    pub fn try_get_index(&self, name: impl AsRef<str>) -> Option<usize> {
+        self.index(self.find(name)?)
     }
 
     pub fn len(&self) -> usize {
         self.positions.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SpaceSimulationStepStats {
+    pub steps: usize,
+    pub rejected: usize,
+    pub adaptive_step: Option<f64>,
+    pub falling_behind: bool,
 }
 
 #[allow(non_snake_case)]
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct SpaceSimulation {
     pub bodies: SpaceBodies,
     pub time: DateTime<Utc>,
     pub G: f64,
+    pub step_stats: SpaceSimulationStepStats,
+}
+
+impl Default for SpaceSimulation {
+    fn default() -> Self {
+        Self {
+            bodies: Default::default(),
+            time: Utc::now(),
+            G: 6.67e-11,
+            step_stats: Default::default(),
+        }
+    }
 }
 
 #[allow(unused_variables)]
@@ -156,116 +372,432 @@ impl SpaceSimulation {
         ))
     }
 
-    pub fn take_step_smooth(&mut self, percision: usize, mut delta_seconds: f64) {
-        fn from_matrix_to_vector(i: usize, j: usize, size: usize) -> usize {
-            if i <= j {
-                return i * size - (i - 1) * i / 2 + j - i;
-            } else {
-                return j * size - (j - 1) * j / 2 + i - j;
-            }
-        }
+    pub fn take_step_smooth(
+        &mut self,
+        params: &SpaceSimulationParams,
+        delta_seconds: f64,
+    ) -> Vec<BodiesCollided> {
+        let start = self.time;
 
-        self.time = self
+        // negative delta propagates backward in time
+        let Some(end) = self
             .time
             .checked_add_signed(Duration::milliseconds((delta_seconds * 1000.0) as i64))
-            .unwrap();
+        else {
+            return vec![];
+        };
+        self.time = end;
+
+        let mut collisions = vec![];
+        let mut elapsed = 0.0;
+
+        // step is split at maneuvers, so burns start and end exactly on time
+        for boundary in self.bodies.maneuver_boundaries(start, end) {
+            let offset = (boundary - start).num_milliseconds() as f64 / 1000.0;
+
+            collisions.extend(self.advance(params, start, elapsed, offset));
+            self.bodies.apply_impulses(boundary, delta_seconds < 0.0);
+
+            elapsed = offset;
+        }
 
-        use itertools::Itertools;
-        use rayon::prelude::*;
+        collisions.extend(self.advance(params, start, elapsed, delta_seconds));
 
-        let bodies_len = self.bodies.len();
+        collisions
+    }
+
+    // integrates from `start` + `from` to `start` + `to` seconds
+    fn advance(
+        &mut self,
+        params: &SpaceSimulationParams,
+        start: DateTime<Utc>,
+        from: f64,
+        to: f64,
+    ) -> Vec<BodiesCollided> {
+        let delta_seconds = to - from;
+
+        if delta_seconds == 0.0 {
+            return vec![];
+        }
+
+        let at = |seconds: f64| start + Duration::milliseconds((seconds * 1000.0) as i64);
 
-        let mut iteration = percision;
-        delta_seconds /= iteration as f64;
+        let burns = self.bodies.active_burns(at(from), at(to));
 
-        let forces_size = (((1 + bodies_len) as f32 / 2.0) * bodies_len as f32) as usize;
+        let mut collisions = vec![];
+        let mut elapsed = 0.0;
 
-        struct Forces(UnsafeCell<Vec<f64>>);
-        unsafe impl Sync for Forces {}
+        let mut merge_collisions = |bodies: &mut SpaceBodies, elapsed: f64| {
+            collisions.extend(bodies.merge_overlapping(at(from + elapsed)));
+        };
 
-        let mut forces = Forces(UnsafeCell::new(vec![0.0; forces_size]));
+        if params.mode.is_analytic() {
+            // conics have no thrust or perturbations, only impulses change them
+            self.bodies.propagate_patched_conics(self.G, delta_seconds);
+            self.step_stats.steps += 1;
 
-        while iteration > 0 {
-            // Fill up forces table
-            (0..self.bodies.len())
-                .combinations(2)
-                .par_bridge()
-                .map(|e| [e[0], e[1]])
-                .for_each_init(
-                    || &forces,
-                    |forces, [i, j]| {
-                        let p1 = self.bodies.positions[i];
-                        let p2 = self.bodies.positions[j];
+            merge_collisions(&mut self.bodies, delta_seconds);
+        } else if params.integrator.is_adaptive() {
+            // step control works with magnitudes, direction is applied to dt only
+            let direction = delta_seconds.signum();
+            let duration = delta_seconds.abs();
 
-                        let force = self.G * self.bodies.masses[i] * self.bodies.masses[j]
-                            / p1.distance_squared(p2);
+            let mut step = self.step_stats.adaptive_step.unwrap_or(duration);
 
-                        let index = from_matrix_to_vector(i, j, bodies_len);
+            while elapsed < duration {
+                let dt = step.min(duration - elapsed);
 
-                        // SAFETY: triangular matrix has unique index for each (i,j)-pair
-                        unsafe {
-                            (*forces.0.get())[index] = force;
-                        }
-                    },
+                let (error, accepted) = self.try_integrate_adaptive(
+                    params,
+                    &burns,
+                    direction * dt,
+                    duration * f64::EPSILON,
                 );
 
-            let forces = &*forces.0.get_mut();
+                let next = DormandPrince45::next_step(dt, error);
 
-            self.bodies
-                .velocities
-                .par_iter_mut()
-                .enumerate()
-                .for_each(|(i, vel)| {
-                    let a: DVec3 = (0..bodies_len)
-                        .par_bridge()
-                        .map(|j| {
-                            if i == j {
-                                return DVec3::ZERO;
-                            }
+                if accepted {
+                    elapsed += dt;
+                    self.step_stats.steps += 1;
 
-                            let p1 = self.bodies.positions[i];
-                            let p2 = self.bodies.positions[j];
+                    merge_collisions(&mut self.bodies, direction * elapsed);
 
-                            let direction = (p1 - p2).normalize();
+                    // do not shrink the step just because it was cut to fit the frame
+                    step = if dt < step { step.min(next) } else { next };
+                } else {
+                    self.step_stats.rejected += 1;
+                    step = next;
+                }
+            }
 
-                            let index = from_matrix_to_vector(i, j, bodies_len);
+            self.step_stats.adaptive_step = Some(step);
+        } else {
+            let dt = delta_seconds / params.percision as f64;
 
-                            let force = forces[index];
+            for _ in 0..params.percision {
+                self.integrate(params, &burns, dt);
 
-                            let a = force / self.bodies.masses[i];
+                elapsed += dt;
+                self.step_stats.steps += 1;
 
-                            -a * direction
-                        })
-                        .sum();
+                merge_collisions(&mut self.bodies, elapsed);
+            }
+        }
 
-                    *vel += a * delta_seconds
-                });
+        collisions
+    }
 
-            self.bodies
-                .positions
-                .par_iter_mut()
-                .zip(self.bodies.velocities.par_iter_mut())
-                .for_each(|(pos, vel)| {
-                    *pos += *vel * delta_seconds;
-                });
+    // propagates in fixed steps of `params.step` up to the `target`, backward if it is in the past,
+    // analytic modes jump to the `target` at once
+    pub fn propagate_to(
+        &mut self,
+        params: &SpaceSimulationParams,
+        target: DateTime<Utc>,
+    ) -> Vec<BodiesCollided> {
+        let mut collisions = vec![];
+
+        loop {
+            // simulation time advances in whole milliseconds
+            let remaining = (target - self.time).num_milliseconds() as f64 / 1000.0;
+            if remaining == 0.0 {
+                break;
+            }
+
+            let delta_seconds = if params.mode.is_analytic() {
+                remaining
+            } else {
+                remaining.abs().min(params.step).copysign(remaining)
+            };
+
+            let before = self.time;
+            collisions.extend(self.take_step_smooth(params, delta_seconds));
+
+            // time is out of range
+            if self.time == before {
+                break;
+            }
+        }
+
+        collisions
+    }
+
+    fn integrate(&mut self, params: &SpaceSimulationParams, burns: &[ActiveBurn], dt: f64) {
+        // merges after previous substeps may have moved or removed burning bodies
+        let burns = &self.bodies.burn_forces(burns);
+        let g = self.G;
+        let SpaceBodies {
+            positions,
+            velocities,
+            masses,
+            zonal_harmonics,
+            non_gravitational,
+            ..
+        } = &mut self.bodies;
+        let masses = &*masses;
+
+        let forces = ForceModel {
+            params,
+            g,
+            masses,
+            zonal_harmonics,
+            non_gravitational,
+            burns,
+        };
+        let acceleration =
+            |positions: &[DVec3], velocities: &[DVec3]| forces.accelerations(positions, velocities);
+
+        params
+            .integrator
+            .integrator()
+            .integrate(positions, velocities, dt, &acceleration);
+    }
+
+    // applies the step only if it is within tolerance or can not be made any smaller
+    fn try_integrate_adaptive(
+        &mut self,
+        params: &SpaceSimulationParams,
+        burns: &[ActiveBurn],
+        dt: f64,
+        min_step: f64,
+    ) -> (f64, bool) {
+        // merges after previous substeps may have moved or removed burning bodies
+        let burns = &self.bodies.burn_forces(burns);
+        let g = self.G;
+        let SpaceBodies {
+            positions,
+            velocities,
+            masses,
+            zonal_harmonics,
+            non_gravitational,
+            ..
+        } = &mut self.bodies;
+        let masses = &*masses;
+
+        let forces = ForceModel {
+            params,
+            g,
+            masses,
+            zonal_harmonics,
+            non_gravitational,
+            burns,
+        };
+        let acceleration =
+            |positions: &[DVec3], velocities: &[DVec3]| forces.accelerations(positions, velocities);
+
+        // barycentric frame, independent of the frame bodies were loaded in
+        let mass = masses.iter().sum::<f64>();
+        let reference = if mass > 0.0 {
+            (
+                positions
+                    .iter()
+                    .zip(masses)
+                    .map(|(p, m)| *p * *m)
+                    .sum::<DVec3>()
+                    / mass,
+                velocities
+                    .iter()
+                    .zip(masses)
+                    .map(|(v, m)| *v * *m)
+                    .sum::<DVec3>()
+                    / mass,
+            )
+        } else {
+            (DVec3::ZERO, DVec3::ZERO)
+        };
+
+        let (new_positions, new_velocities, error) = DormandPrince45.try_step(
+            positions,
+            velocities,
+            dt,
+            params.tolerance,
+            reference,
+            &acceleration,
+        );
+
+        let accepted = error <= 1.0 || dt.abs() <= min_step;
 
-            iteration -= 1;
+        if accepted {
+            positions.copy_from_slice(&new_positions);
+            velocities.copy_from_slice(&new_velocities);
         }
+
+        (error, accepted)
     }
 }
 
 pub mod systems {
-    use super::{SpaceSimulation, SpaceSimulationParams};
+    use super::{BodiesCollided, SpaceSimulation, SpaceSimulationParams};
+    use crate::space::simulation::{SpaceSimulationEventDetected, SpaceSimulationEvents};
     use bevy::prelude::*;
 
+    // simulation advances in fixed steps of simulated time, so trajectories
+    // do not depend on frame rate, frames only decide how many steps to take
     pub fn simulation_take_step(
         time: Res<Time>,
         simulation_params: Res<SpaceSimulationParams>,
         mut simulation: ResMut<SpaceSimulation>,
+        mut collisions: EventWriter<BodiesCollided>,
+        mut events: ResMut<SpaceSimulationEvents>,
+        mut detected: EventWriter<SpaceSimulationEventDetected>,
+        mut accumulator: Local<f64>,
     ) {
-        simulation.take_step_smooth(
-            simulation_params.percision,
-            time.delta_seconds_f64() * simulation_params.speed,
-        );
+        *accumulator += time.delta_seconds_f64() * simulation_params.speed;
+
+        simulation.step_stats.steps = 0;
+        simulation.step_stats.rejected = 0;
+
+        // detectors interpolate motion within a step, so analytic jumps are split
+        // into regular steps while there are any
+        let analytic_step = if events.detectors.is_empty() {
+            f64::INFINITY
+        } else {
+            simulation_params.step
+        };
+
+        let mut take_step = |simulation: &mut SpaceSimulation, delta_seconds: f64| {
+            let (collided, found) = events.take_step(simulation, &simulation_params, delta_seconds);
+
+            collisions.send_batch(collided);
+            detected.send_batch(found.into_iter().map(SpaceSimulationEventDetected));
+        };
+
+        // analytic modes are exact for any step, accumulated time is taken at once,
+        // in whole milliseconds the simulation time advances in
+        if simulation_params.mode.is_analytic() {
+            let delta_seconds = (*accumulator * 1000.0).trunc() / 1000.0;
+
+            let mut remaining = delta_seconds;
+
+            loop {
+                let step = remaining.abs().min(analytic_step).copysign(remaining);
+                take_step(&mut simulation, step);
+
+                remaining -= step;
+
+                if remaining == 0.0 {
+                    break;
+                }
+            }
+
+            *accumulator -= delta_seconds;
+            simulation.step_stats.falling_behind = false;
+            return;
+        }
+
+        let mut steps = 0;
+
+        // accumulator is negative when time runs backward
+        while accumulator.abs() >= simulation_params.step
+            && steps < simulation_params.max_steps_per_frame
+        {
+            let delta_seconds = simulation_params.step.copysign(*accumulator);
+
+            take_step(&mut simulation, delta_seconds);
+
+            *accumulator -= delta_seconds;
+            steps += 1;
+        }
+
+        // work is capped, so requested speed can not be kept up with,
+        // time that did not fit is dropped instead of piling up
+        simulation.step_stats.falling_behind = accumulator.abs() >= simulation_params.step;
+
+        if simulation.step_stats.falling_behind {
+            *accumulator %= simulation_params.step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+    use chrono::{TimeZone, Utc};
+
+    use super::{SpaceBodies, SpaceBody};
+    use crate::space::simulation::{SpaceBodyManeuver, SpaceBodyManeuverKind, SpaceBodySpacecraft};
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let mut bodies = SpaceBodies::default();
+
+        let first = bodies.insert("a".into(), SpaceBody::default()).unwrap();
+
+        assert!(bodies.insert("a".into(), SpaceBody::default()).is_err());
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies.find("a"), Some(first));
+
+        bodies.remove(first);
+
+        assert_eq!(bodies.find("a"), None);
+        assert!(bodies.insert("a".into(), SpaceBody::default()).is_ok());
+    }
+
+    #[test]
+    fn handles_survive_removal_of_other_bodies() {
+        let mut bodies = SpaceBodies::default();
+
+        let a = bodies.insert("a".into(), SpaceBody::default()).unwrap();
+        let b = bodies.insert("b".into(), SpaceBody::default()).unwrap();
+        let c = bodies.insert("c".into(), SpaceBody::default()).unwrap();
+
+        // the last body takes the place of the removed one
+        assert_eq!(bodies.remove(a), Some(0));
+        assert_eq!(bodies.index(c), Some(0));
+        assert_eq!(bodies.index(b), Some(1));
+        assert_eq!(bodies.index(a), None);
+        assert_eq!(bodies.remove(a), None);
+
+        // slot of the removed body is reused by a new handle
+        let d = bodies.insert("d".into(), SpaceBody::default()).unwrap();
+
+        assert_ne!(a, d);
+        assert_eq!(bodies.index(a), None);
+        assert_eq!(bodies.index(d), Some(2));
+        assert_eq!(bodies.name(d).map(String::as_str), Some("d"));
+    }
+
+    #[test]
+    fn exported_maneuvers_refer_to_bodies_by_name() {
+        let mut bodies = SpaceBodies::default();
+
+        let maneuver = |reference: &str| SpaceBodyManeuver {
+            epoch: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            reference: reference.to_string(),
+            kind: SpaceBodyManeuverKind::Impulse { delta_v: DVec3::X },
+        };
+
+        // reference to the moon stays pending until it is inserted
+        let craft = bodies
+            .insert(
+                "craft".into(),
+                SpaceBody {
+                    spacecraft: Some(SpaceBodySpacecraft {
+                        maneuvers: vec![maneuver("earth"), maneuver("moon")],
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let earth = bodies.insert("earth".into(), SpaceBody::default()).unwrap();
+
+        let references = |bodies: &SpaceBodies| {
+            bodies
+                .to_body(craft)
+                .unwrap()
+                .spacecraft
+                .unwrap()
+                .maneuvers
+                .into_iter()
+                .map(|maneuver| maneuver.reference)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(references(&bodies), ["earth", "moon"]);
+
+        // maneuvers relative to removed bodies can not be saved
+        bodies.remove(earth);
+
+        assert_eq!(references(&bodies), ["moon"]);
+        assert!(bodies.to_body(earth).is_none());
     }
 }
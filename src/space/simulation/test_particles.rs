@@ -0,0 +1,87 @@
+use bevy::math::DVec3;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{BodyId, SpaceBodies, SpaceBody};
+
+// ring of test particles on near circular orbits around the `center` body,
+// `inclination` and `eccentricity` spread make it a belt rather than a flat disk
+#[derive(Debug, Clone)]
+pub struct TestParticleRing {
+    pub name: String,
+    pub center: BodyId,
+    pub count: usize,
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    // normal of the ring plane
+    pub normal: DVec3,
+    pub inclination: f64,
+    pub eccentricity: f64,
+    pub particle_radius: f64,
+    pub seed: u64,
+}
+
+impl TestParticleRing {
+    // 2.1 - 3.3 AU around the Sun, in plane of the ecliptic
+    pub fn main_belt(sun: BodyId, count: usize) -> Self {
+        const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+
+        Self {
+            name: "main belt".into(),
+            center: sun,
+            count,
+            inner_radius: 2.1 * ASTRONOMICAL_UNIT,
+            outer_radius: 3.3 * ASTRONOMICAL_UNIT,
+            // planets orbit with angular momentum along y in simulation frame
+            normal: DVec3::Y,
+            inclination: 10f64.to_radians(),
+            eccentricity: 0.15,
+            particle_radius: 50_000.0,
+            seed: 0,
+        }
+    }
+
+    // returns None if the center body is unknown
+    pub fn generate(&self, bodies: &SpaceBodies, g: f64) -> Option<Vec<(String, SpaceBody)>> {
+        let center = bodies.index(self.center)?;
+        let center_position = bodies.positions()[center];
+        let center_velocity = bodies.velocities()[center];
+        let gm = g * bodies.masses()[center];
+
+        let normal = self.normal.normalize();
+        let (u, v) = normal.any_orthonormal_pair();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let particles = (0..self.count)
+            .map(|i| {
+                // uniform over area of the ring
+                let radius =
+                    (rng.gen_range(self.inner_radius.powi(2)..=self.outer_radius.powi(2))).sqrt();
+                let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+
+                let radial = u * angle.cos() + v * angle.sin();
+                let prograde = normal.cross(radial);
+
+                // tilt orbit plane around the radial direction
+                let tilt = rng.gen_range(-self.inclination..=self.inclination);
+                let prograde = prograde * tilt.cos() + normal * tilt.sin();
+
+                // eccentricity as a speed offset from the circular orbit
+                let speed = (gm / radius).sqrt()
+                    * (1.0 + rng.gen_range(-self.eccentricity..=self.eccentricity) / 2.0);
+
+                let body = SpaceBody {
+                    position: center_position + radial * radius,
+                    velocity: center_velocity + prograde * speed,
+                    mass: 0.0,
+                    radius: self.particle_radius,
+                    ..Default::default()
+                };
+
+                (format!("{} {i}", self.name), body)
+            })
+            .collect();
+
+        Some(particles)
+    }
+}
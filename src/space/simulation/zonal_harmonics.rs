@@ -0,0 +1,42 @@
+use bevy::math::DVec3;
+
+// zonal harmonics of an axially symmetric body, with pole fixed in space
+#[derive(Debug, Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct SpaceBodyZonalHarmonics {
+    pub j2: f64,
+    #[serde(default)]
+    pub j3: f64,
+    #[serde(default)]
+    pub j4: f64,
+    // equatorial radius the coefficients are normalized to
+    pub reference_radius: f64,
+    pub pole: DVec3,
+}
+
+impl SpaceBodyZonalHarmonics {
+    // acceleration of a point at `r` from the body of `mass`, excluding the point mass term
+    pub fn acceleration(&self, g: f64, mass: f64, r: DVec3) -> DVec3 {
+        let gm = g * mass;
+        let distance_squared = r.length_squared();
+        let distance = distance_squared.sqrt();
+
+        let z = r.dot(self.pole);
+        let u = z * z / distance_squared;
+        let ratio = self.reference_radius / distance;
+
+        let j2 = -1.5 * self.j2 * ratio.powi(2) * ((1.0 - 5.0 * u) * r + 2.0 * z * self.pole);
+
+        let j3 = -2.5
+            * self.j3
+            * ratio.powi(3)
+            * ((3.0 * z - 7.0 * z * u) / distance * r
+                + (3.0 * z * z - 0.6 * distance_squared) / distance * self.pole);
+
+        let j4 = 1.875
+            * self.j4
+            * ratio.powi(4)
+            * ((1.0 - 14.0 * u + 21.0 * u * u) * r + (4.0 - 28.0 / 3.0 * u) * z * self.pole);
+
+        gm / (distance_squared * distance) * (j2 + j3 + j4)
+    }
+}
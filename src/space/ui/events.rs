@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::{BodyRef, BodyTrail},
+    scene::markers::FocusedBody,
+    simulation::{
+        BodyId, SeekSpaceSimulationRequest, SpaceEventDetector, SpaceEventDetectorKind,
+        SpaceEventKind, SpaceSimulation, SpaceSimulationEvents,
+    },
+};
+
+pub fn events_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    simulation: Res<SpaceSimulation>,
+    mut events: ResMut<SpaceSimulationEvents>,
+    bodies: Query<&BodyRef>,
+    body_trails: Query<&BodyTrail>,
+    focused_body: Marker<FocusedBody>,
+    mut seek_ev: EventWriter<SeekSpaceSimulationRequest>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let primary = bodies.get(focused_body[Primary]).ok().map(|BodyRef(b)| *b);
+    let secondary = bodies
+        .get(focused_body[Secondary])
+        .ok()
+        .map(|BodyRef(b)| *b);
+
+    // orbit is watched relative to the anchor of the trail, as in the orbit panel
+    let central = primary.and_then(|body| {
+        let anchor = body_trails
+            .iter()
+            .find(|trail| trail.body == body)
+            .and_then(|trail| trail.anchor)
+            .and_then(|anchor| simulation.bodies.index(anchor));
+
+        let index = simulation.bodies.index(body)?;
+        let central = anchor.or_else(|| simulation.bodies.dominant_attractor(index))?;
+
+        Some(simulation.bodies.ids()[central])
+    });
+
+    let name = |body: BodyId| {
+        simulation
+            .bodies
+            .name(body)
+            .map_or("—", |name| name.as_str())
+    };
+
+    let events = &mut *events;
+
+    egui::Window::new("События")
+        .resizable(false)
+        .default_open(false)
+        .default_pos((16.0, 480.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.checkbox(&mut events.pause_on_events, "останавливаться на событиях");
+
+            ui.horizontal(|ui| {
+                let (Some(body), Some(central)) = (primary, central) else { return };
+
+                for (kind, label) in [
+                    (SpaceEventDetectorKind::Apsides, "апсиды выбранного"),
+                    (SpaceEventDetectorKind::Nodes, "узлы выбранного"),
+                ] {
+                    if ui
+                        .button(RichText::new(label).color(Color32::LIGHT_YELLOW))
+                        .clicked()
+                    {
+                        events.add_detector(SpaceEventDetector {
+                            kind,
+                            body,
+                            other: central,
+                        });
+                    }
+                }
+            });
+
+            if let (Some(body), Some(other)) = (primary, secondary) {
+                if body != other
+                    && ui
+                        .button(
+                            RichText::new("сближение выбранной пары").color(Color32::LIGHT_YELLOW),
+                        )
+                        .clicked()
+                {
+                    events.add_detector(SpaceEventDetector {
+                        kind: SpaceEventDetectorKind::ClosestApproach,
+                        body,
+                        other,
+                    });
+                }
+            }
+
+            let mut removed = None;
+
+            for (i, detector) in events.detectors.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        Color32::LIGHT_BLUE,
+                        format!(
+                            "{}: {} — {}",
+                            match detector.kind {
+                                SpaceEventDetectorKind::Apsides => "апсиды",
+                                SpaceEventDetectorKind::Nodes => "узлы",
+                                SpaceEventDetectorKind::ClosestApproach => "сближение",
+                            },
+                            name(detector.body),
+                            name(detector.other)
+                        ),
+                    );
+
+                    if ui
+                        .button(RichText::new("Удалить").color(Color32::LIGHT_RED))
+                        .clicked()
+                    {
+                        removed = Some(i);
+                    }
+                });
+            }
+
+            if let Some(i) = removed {
+                events.detectors.remove(i);
+            }
+
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    // latest events first
+                    for event in events.log().rev() {
+                        ui.horizontal(|ui| {
+                            if ui.button("перейти").clicked() {
+                                seek_ev.send(SeekSpaceSimulationRequest { time: event.time });
+                            }
+
+                            ui.label(event.time.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.colored_label(
+                                Color32::LIGHT_YELLOW,
+                                match event.kind {
+                                    SpaceEventKind::Periapsis => "перицентр",
+                                    SpaceEventKind::Apoapsis => "апоцентр",
+                                    SpaceEventKind::AscendingNode => "восходящий узел",
+                                    SpaceEventKind::DescendingNode => "нисходящий узел",
+                                    SpaceEventKind::ClosestApproach => "сближение",
+                                },
+                            );
+                            ui.label(format!(
+                                "{} — {}, {:.6e} км",
+                                event.body_name,
+                                event.other_name,
+                                event.distance / 1000.0
+                            ));
+                        });
+                    }
+                });
+
+            if ui
+                .button(RichText::new("Очистить").color(Color32::LIGHT_YELLOW))
+                .clicked()
+            {
+                events.clear_log();
+            }
+        });
+}
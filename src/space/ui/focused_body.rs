@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::BodyRef,
+    scene::{markers::FocusedBody, RemoveBodyRequest},
+    simulation::{
+        SpaceBodiesEdited, SpaceSimulation, SpaceSimulationDiagnostics, SpaceSimulationHistory,
+    },
+};
+
+pub fn focused_body_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut simulation: ResMut<SpaceSimulation>,
+    mut history: ResMut<SpaceSimulationHistory>,
+    mut diagnostics: ResMut<SpaceSimulationDiagnostics>,
+    bodies: Query<&BodyRef>,
+    focused_body: Marker<FocusedBody>,
+    mut remove_ev: EventWriter<RemoveBodyRequest>,
+    mut edited_ev: EventWriter<SpaceBodiesEdited>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let Ok(BodyRef(id)) = bodies.get(focused_body[Primary]) else { return };
+    let Some(index) = simulation.bodies.index(*id) else { return };
+
+    let name = simulation.bodies.names()[index].clone();
+
+    // values are edited in kilometers and degrees, only the changed ones are written back
+    let mut mass = simulation.bodies.masses()[index];
+    let mut radius = simulation.bodies.radiuses()[index] / 1000.0;
+    let mut position = simulation.bodies.positions()[index] / 1000.0;
+    let mut velocity = simulation.bodies.velocities()[index] / 1000.0;
+    let mut rotation = simulation.bodies.rotations()[index].clone();
+
+    let (x, y, z) = rotation.initial.to_euler(EulerRot::XYZ);
+    let mut orientation = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+
+    let mut mass_changed = false;
+    let mut radius_changed = false;
+    let mut position_changed = false;
+    let mut velocity_changed = false;
+    let mut rotation_changed = false;
+    let mut orientation_changed = false;
+
+    egui::Window::new(format!("Тело: {name}"))
+        .id(egui::Id::new("focused_body_window"))
+        .resizable(false)
+        .default_pos((320.0, 120.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                let speed = mass.abs().max(1.0) * 1e-3;
+
+                mass_changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut mass)
+                            .speed(speed)
+                            .clamp_range(0.0..=f64::INFINITY)
+                            .prefix("масса: ")
+                            .suffix(" кг"),
+                    )
+                    .changed();
+
+                for (factor, label) in [(10.0, "×10"), (0.1, "÷10")] {
+                    if ui
+                        .button(RichText::new(label).color(Color32::LIGHT_YELLOW))
+                        .clicked()
+                    {
+                        mass *= factor;
+                        mass_changed = true;
+                    }
+                }
+            });
+
+            let speed = radius.max(1.0) * 1e-3;
+
+            radius_changed |= ui
+                .add(
+                    egui::DragValue::new(&mut radius)
+                        .speed(speed)
+                        .clamp_range(1e-3..=f64::INFINITY)
+                        .prefix("радиус: ")
+                        .suffix(" км"),
+                )
+                .changed();
+
+            for (vector, label, suffix, changed) in [
+                (&mut position, "положение", " км", &mut position_changed),
+                (&mut velocity, "скорость", " км/с", &mut velocity_changed),
+            ] {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{label}:"));
+
+                    let speed = vector.length().max(1.0) * 1e-4;
+
+                    for value in [&mut vector.x, &mut vector.y, &mut vector.z] {
+                        *changed |= ui
+                            .add(egui::DragValue::new(value).speed(speed).suffix(suffix))
+                            .changed();
+                    }
+                });
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("ориентация:");
+
+                for value in &mut orientation {
+                    orientation_changed |= ui
+                        .add(egui::DragValue::new(value).speed(0.1).suffix("°"))
+                        .changed();
+                }
+            });
+
+            rotation_changed |= ui
+                .add(
+                    egui::DragValue::new(&mut rotation.sideral_rotation_offset)
+                        .speed(0.01)
+                        .prefix("начальный угол вращения: ")
+                        .suffix(" рад"),
+                )
+                .changed();
+
+            rotation_changed |= ui
+                .add(
+                    egui::DragValue::new(&mut rotation.sideral_rotation_speed)
+                        .speed(1e-7)
+                        .max_decimals(12)
+                        .prefix("скорость вращения: ")
+                        .suffix(" рад/с"),
+                )
+                .changed();
+
+            ui.separator();
+
+            if ui
+                .button(RichText::new("Удалить").color(Color32::LIGHT_RED))
+                .clicked()
+            {
+                remove_ev.send(RemoveBodyRequest(*id));
+            }
+        });
+
+    if mass_changed {
+        simulation.bodies.masses_mut()[index] = mass;
+    }
+    if radius_changed {
+        simulation.bodies.radiuses_mut()[index] = radius * 1000.0;
+    }
+    if position_changed {
+        simulation.bodies.positions_mut()[index] = position * 1000.0;
+    }
+    if velocity_changed {
+        simulation.bodies.velocities_mut()[index] = velocity * 1000.0;
+    }
+    if orientation_changed {
+        let [x, y, z] = orientation.map(f32::to_radians);
+        rotation.initial = Quat::from_euler(EulerRot::XYZ, x, y, z);
+    }
+    if rotation_changed || orientation_changed {
+        simulation.bodies.rotations_mut()[index] = rotation;
+    }
+
+    // recorded states and invariants belong to the body as it was
+    if mass_changed || radius_changed || position_changed || velocity_changed {
+        history.clear();
+        diagnostics.reset();
+        edited_ev.send(SpaceBodiesEdited);
+    }
+}
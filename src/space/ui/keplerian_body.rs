@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::BodyRef,
+    scene::{markers::FocusedBody, SpawnKeplerianBodyRequest},
+    simulation::{dominant_body, KeplerianBody, OrbitalElements, SpaceSimulation},
+};
+
+// values as they are entered, distances are in km and angles are in degrees
+pub struct KeplerianBodyForm {
+    pub name: String,
+    pub parent: String,
+    pub epoch: String,
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub mean_anomaly: f64,
+    pub mass: f64,
+    pub radius: f64,
+}
+
+impl Default for KeplerianBodyForm {
+    fn default() -> Self {
+        Self {
+            name: "новое тело".into(),
+            parent: String::new(),
+            epoch: String::new(),
+            semi_major_axis: 149_597_870.7,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            longitude_of_ascending_node: 0.0,
+            argument_of_periapsis: 0.0,
+            mean_anomaly: 0.0,
+            mass: 0.0,
+            radius: 1000.0,
+        }
+    }
+}
+
+impl KeplerianBodyForm {
+    fn elements(&self) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis: self.semi_major_axis * 1000.0,
+            eccentricity: self.eccentricity,
+            inclination: self.inclination.to_radians(),
+            longitude_of_ascending_node: self.longitude_of_ascending_node.to_radians(),
+            argument_of_periapsis: self.argument_of_periapsis.to_radians(),
+            mean_anomaly: self.mean_anomaly.to_radians(),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn keplerian_body_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut form: Local<KeplerianBodyForm>,
+    simulation: Res<SpaceSimulation>,
+    bodies: Query<&BodyRef>,
+    focused_body: Marker<FocusedBody>,
+    mut spawn_ev: EventWriter<SpawnKeplerianBodyRequest>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let form = &mut *form;
+
+    egui::Window::new("Тело по элементам орбиты")
+        .resizable(false)
+        .default_open(false)
+        .default_pos((16.0, 640.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("имя:");
+                ui.text_edit_singleline(&mut form.name);
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("вокруг:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut form.parent)
+                        .hint_text("самого массивного")
+                        .desired_width(96.0),
+                );
+
+                let focused = bodies
+                    .get(focused_body[Primary])
+                    .ok()
+                    .and_then(|BodyRef(body)| simulation.bodies.name(*body));
+
+                if let Some(body) = focused {
+                    if ui
+                        .button(RichText::new("выбранного").color(Color32::LIGHT_YELLOW))
+                        .clicked()
+                    {
+                        form.parent = body.clone();
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("эпоха:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut form.epoch)
+                        .hint_text("текущее время симуляции"),
+                );
+            });
+
+            // empty epoch means elements are given for the current time
+            let epoch = match form.epoch.trim() {
+                "" => Some(simulation.time),
+                epoch => chrono::DateTime::parse_from_rfc3339(epoch)
+                    .ok()
+                    .map(Into::into),
+            };
+
+            ui.add(
+                egui::DragValue::new(&mut form.semi_major_axis)
+                    .speed(1000.0)
+                    .prefix("большая полуось: ")
+                    .suffix(" км"),
+            );
+            ui.add(
+                egui::DragValue::new(&mut form.eccentricity)
+                    .speed(0.001)
+                    .clamp_range(0.0..=f64::INFINITY)
+                    .prefix("эксцентриситет: "),
+            );
+
+            for (value, prefix) in [
+                (&mut form.inclination, "наклонение: "),
+                (
+                    &mut form.longitude_of_ascending_node,
+                    "долгота восходящего узла: ",
+                ),
+                (&mut form.argument_of_periapsis, "аргумент перицентра: "),
+                (&mut form.mean_anomaly, "средняя аномалия: "),
+            ] {
+                ui.add(
+                    egui::DragValue::new(value)
+                        .speed(0.1)
+                        .prefix(prefix)
+                        .suffix("°"),
+                );
+            }
+
+            ui.add(
+                egui::DragValue::new(&mut form.mass)
+                    .speed(1e15)
+                    .clamp_range(0.0..=f64::INFINITY)
+                    .prefix("масса: ")
+                    .suffix(" кг"),
+            );
+            ui.add(
+                egui::DragValue::new(&mut form.radius)
+                    .speed(1.0)
+                    .clamp_range(0.0..=f64::INFINITY)
+                    .prefix("радиус: ")
+                    .suffix(" км"),
+            );
+
+            let elements = form.elements();
+
+            if !elements.is_valid() {
+                ui.colored_label(Color32::LIGHT_RED, "полуось и эксцентриситет несовместимы");
+            }
+            if epoch.is_none() {
+                ui.colored_label(Color32::LIGHT_RED, "эпоха не в формате RFC 3339");
+            }
+
+            // parent is entered by name, empty name means the dominant body
+            let parent = match form.parent.trim() {
+                "" => dominant_body(simulation.bodies.masses())
+                    .map(|index| simulation.bodies.ids()[index]),
+                parent => simulation.bodies.find(parent),
+            };
+
+            if parent.is_none() {
+                ui.colored_label(Color32::LIGHT_RED, "нет такого тела");
+            }
+
+            let Some(epoch) = epoch.filter(|_| elements.is_valid()) else { return };
+            let Some(parent) = parent else { return };
+
+            if ui
+                .button(RichText::new("Добавить").color(Color32::LIGHT_YELLOW))
+                .clicked()
+            {
+                spawn_ev.send(SpawnKeplerianBodyRequest(KeplerianBody {
+                    name: form.name.clone(),
+                    parent,
+                    elements,
+                    epoch,
+                    mass: form.mass,
+                    radius: form.radius * 1000.0,
+                }));
+            }
+        });
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::{BodyRef, ShowLagrangePoints},
+    scene::{markers::FocusedBody, SpawnLagrangePointParticleRequest},
+    simulation::SpaceSimulation,
+};
+
+pub fn lagrange_points_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut show_lagrange_points: ResMut<ShowLagrangePoints>,
+    simulation: Res<SpaceSimulation>,
+    bodies: Query<&BodyRef>,
+    focused_body: Marker<FocusedBody>,
+    mut spawn_ev: EventWriter<SpawnLagrangePointParticleRequest>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let Ok(BodyRef(primary_id)) = bodies.get(focused_body[Primary]) else { return };
+    let Ok(BodyRef(secondary_id)) = bodies.get(focused_body[Secondary]) else { return };
+
+    let Some(i) = simulation.bodies.index(*primary_id) else { return };
+    let Some(j) = simulation.bodies.index(*secondary_id) else { return };
+
+    let (primary, secondary) = (&simulation.bodies.names()[i], &simulation.bodies.names()[j]);
+
+    let points = simulation.bodies.lagrange_points(i, j);
+
+    egui::Window::new(format!("Точки Лагранжа: {primary} — {secondary}"))
+        .resizable(false)
+        .default_open(false)
+        .default_pos((320.0, 16.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.checkbox(&mut show_lagrange_points.value, "показывать");
+
+            let Some(points) = points else {
+                ui.colored_label(
+                    Color32::LIGHT_RED,
+                    "пара не движется друг относительно друга",
+                );
+                return;
+            };
+
+            // distances are measured from the lighter body of the pair
+            let lighter = if simulation.bodies.masses()[i] < simulation.bodies.masses()[j] {
+                i
+            } else {
+                j
+            };
+
+            for (k, position) in points.positions.iter().enumerate() {
+                ui.label(format!(
+                    "L{}: {:.6e} км от {}",
+                    k + 1,
+                    position.distance(simulation.bodies.positions()[lighter]) / 1000.0,
+                    simulation.bodies.names()[lighter]
+                ));
+            }
+
+            if ui
+                .button(RichText::new("Частица в L4").color(Color32::LIGHT_YELLOW))
+                .clicked()
+            {
+                spawn_ev.send(SpawnLagrangePointParticleRequest {
+                    primary: *primary_id,
+                    secondary: *secondary_id,
+                    point: 3,
+                });
+            }
+        });
+}
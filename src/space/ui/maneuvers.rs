@@ -0,0 +1,219 @@
+use bevy::{math::DVec3, prelude::*};
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::BodyRef,
+    scene::markers::FocusedBody,
+    simulation::{
+        dominant_body, SpaceBodiesEdited, SpaceBodyManeuver, SpaceBodyManeuverKind,
+        SpaceBodySpacecraft, SpaceSimulation, SpaceSimulationHistory,
+    },
+};
+
+pub fn maneuvers_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut simulation: ResMut<SpaceSimulation>,
+    mut history: ResMut<SpaceSimulationHistory>,
+    bodies: Query<&BodyRef>,
+    focused_body: Marker<FocusedBody>,
+    mut edited_ev: EventWriter<SpaceBodiesEdited>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let Ok(BodyRef(body)) = bodies.get(focused_body[Primary]) else { return };
+    let Some(index) = simulation.bodies.index(*body) else { return };
+
+    let name = simulation.bodies.names()[index].clone();
+
+    // maneuvers are relative to the secondary body, or to the Sun if there is none
+    let default_reference = match bodies.get(focused_body[Secondary]) {
+        Ok(BodyRef(reference)) if reference != body => Some(*reference),
+        _ => dominant_body(simulation.bodies.masses())
+            .map(|i| simulation.bodies.ids()[i])
+            .filter(|reference| reference != body),
+    };
+
+    // test particles are too many to list
+    let references = simulation
+        .bodies
+        .ids()
+        .iter()
+        .zip(simulation.bodies.names())
+        .zip(simulation.bodies.masses())
+        .filter(|((id, _), mass)| *id != body && **mass > 0.0)
+        .map(|((id, name), _)| (*id, name.clone()))
+        .collect::<Vec<_>>();
+
+    let time = simulation.time;
+    let mut spacecraft = simulation.bodies.spacecraft()[index].clone();
+    let mut changed = false;
+
+    egui::Window::new(format!("Манёвры: {name}"))
+        .resizable(false)
+        .default_pos((16.0, 320.0))
+        .show(ctx.ctx_mut(), |ui| {
+            let mut is_spacecraft = spacecraft.is_some();
+
+            if ui
+                .checkbox(&mut is_spacecraft, "космический аппарат")
+                .changed()
+            {
+                spacecraft = is_spacecraft.then(SpaceBodySpacecraft::default);
+                changed = true;
+            }
+
+            let Some(spacecraft) = &mut spacecraft else { return };
+
+            let seconds = |duration: chrono::Duration| duration.num_milliseconds() as f64 / 1000.0;
+
+            let mut removed = None;
+
+            for (i, maneuver) in spacecraft.maneuvers.iter_mut().enumerate() {
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::LIGHT_BLUE,
+                            match maneuver.kind {
+                                SpaceBodyManeuverKind::Impulse { .. } => "Импульс",
+                                SpaceBodyManeuverKind::Burn { .. } => "Работа двигателя",
+                            },
+                        );
+
+                        if ui
+                            .button(RichText::new("Удалить").color(Color32::LIGHT_RED))
+                            .clicked()
+                        {
+                            removed = Some(i);
+                        }
+                    });
+
+                    ui.label(maneuver.epoch.to_string());
+
+                    // epoch is edited relative to the current time of the simulation
+                    let mut offset = seconds(maneuver.epoch - time);
+
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut offset)
+                                .speed(60.0)
+                                .prefix("через: ")
+                                .suffix(" сек."),
+                        )
+                        .changed()
+                    {
+                        maneuver.epoch =
+                            time + chrono::Duration::milliseconds((offset * 1000.0) as i64);
+                        changed = true;
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label("относительно:");
+
+                        let selected = match simulation.bodies.name(maneuver.reference) {
+                            Some(name) => RichText::new(name),
+                            None => RichText::new("удалено").color(Color32::LIGHT_RED),
+                        };
+
+                        egui::ComboBox::from_id_source(("maneuver_reference", i))
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (id, name) in &references {
+                                    changed |= ui
+                                        .selectable_value(&mut maneuver.reference, *id, name)
+                                        .changed();
+                                }
+                            });
+                    });
+
+                    let (vector, suffix) = match &mut maneuver.kind {
+                        SpaceBodyManeuverKind::Impulse { delta_v } => (delta_v, " м/с"),
+                        SpaceBodyManeuverKind::Burn {
+                            acceleration,
+                            duration,
+                        } => {
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(duration)
+                                        .speed(1.0)
+                                        .clamp_range(0.0..=f64::INFINITY)
+                                        .prefix("длительность: ")
+                                        .suffix(" сек."),
+                                )
+                                .changed();
+
+                            (acceleration, " м/с²")
+                        }
+                    };
+
+                    for (value, prefix) in [
+                        (&mut vector.x, "по ходу: "),
+                        (&mut vector.y, "по нормали: "),
+                        (&mut vector.z, "по радиусу: "),
+                    ] {
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(value)
+                                    .speed(0.01)
+                                    .prefix(prefix)
+                                    .suffix(suffix),
+                            )
+                            .changed();
+                    }
+                });
+            }
+
+            if let Some(i) = removed {
+                spacecraft.maneuvers.remove(i);
+                changed = true;
+            }
+
+            let Some(reference) = default_reference else { return };
+
+            let mut add_maneuver = |kind| {
+                spacecraft.maneuvers.push(SpaceBodyManeuver {
+                    epoch: time + chrono::Duration::hours(1),
+                    reference,
+                    kind,
+                });
+                changed = true;
+            };
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(RichText::new("Добавить импульс").color(Color32::LIGHT_YELLOW))
+                    .clicked()
+                {
+                    add_maneuver(SpaceBodyManeuverKind::Impulse {
+                        delta_v: DVec3::ZERO,
+                    });
+                }
+                if ui
+                    .button(RichText::new("Добавить работу двигателя").color(Color32::LIGHT_YELLOW))
+                    .clicked()
+                {
+                    add_maneuver(SpaceBodyManeuverKind::Burn {
+                        acceleration: DVec3::ZERO,
+                        duration: 600.0,
+                    });
+                }
+            });
+        });
+
+    if changed {
+        simulation.bodies.spacecraft_mut()[index] = spacecraft;
+
+        // recorded states follow the old plan
+        history.clear();
+        edited_ev.send(SpaceBodiesEdited);
+    }
+}
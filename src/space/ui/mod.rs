@@ -7,12 +7,53 @@ use bevy_egui::{
 use super::{
     display::{ToggleViewModeEvent, ViewMode},
     nasa_horizons::{NasaBodyAddition, SpawnNasaBodyRequest},
+    scene::SpawnTestParticlesRequest,
     simulation::{
-        systems::ToggleSpaceSimulationStateEvent, SpaceSimulation, SpaceSimulationParams,
-        SpaceSimulationState,
+        dominant_body, systems::ToggleSpaceSimulationStateEvent, SeekSpaceSimulationRequest,
+        SpaceSimulation, SpaceSimulationDiagnostics, SpaceSimulationForceBackend,
+        SpaceSimulationHistory, SpaceSimulationIntegrator, SpaceSimulationMode,
+        SpaceSimulationParams, SpaceSimulationState, TestParticleRing,
     },
 };
 
+pub mod events;
+pub use events::*;
+
+pub mod focused_body;
+pub use focused_body::*;
+
+pub mod keplerian_body;
+pub use keplerian_body::*;
+
+pub mod lagrange_points;
+pub use lagrange_points::*;
+
+pub mod maneuvers;
+pub use maneuvers::*;
+
+pub mod orbital_elements;
+pub use orbital_elements::*;
+
+pub mod placement;
+pub use placement::*;
+
+pub mod prediction;
+pub use prediction::*;
+
+pub mod scenario;
+pub use scenario::*;
+
+// values of the test particles group as they are entered
+pub struct TestParticlesForm {
+    pub count: usize,
+}
+
+impl Default for TestParticlesForm {
+    fn default() -> Self {
+        Self { count: 2000 }
+    }
+}
+
 #[derive(Resource)]
 pub struct ShowUI {
     pub value: bool,
@@ -26,7 +67,7 @@ impl Default for ShowUI {
 
 pub fn ui_system(
     mut ctx: ResMut<EguiContext>,
-    mut show_ui: Local<ShowUI>,
+    mut show_ui: ResMut<ShowUI>,
     view_mode: Res<State<ViewMode>>,
     mut view_mode_ev: EventWriter<ToggleViewModeEvent>,
     space_simulation_state: Res<State<SpaceSimulationState>>,
@@ -36,6 +77,11 @@ pub fn ui_system(
     mut nasa_body_addition_ev: EventWriter<NasaBodyAddition>,
     mut space_simulation_params: ResMut<SpaceSimulationParams>,
     mut space_simulation: ResMut<SpaceSimulation>,
+    mut diagnostics: ResMut<SpaceSimulationDiagnostics>,
+    history: Res<SpaceSimulationHistory>,
+    mut seek_ev: EventWriter<SeekSpaceSimulationRequest>,
+    mut test_particles_ev: EventWriter<SpawnTestParticlesRequest>,
+    mut test_particles_form: Local<TestParticlesForm>,
 ) {
     // ESC
     if keyboard.just_pressed(ScanCode(1)) {
@@ -160,6 +206,35 @@ pub fn ui_system(
                     {
                         insert_bodies_local("./assets/bodies/all-bodies.json");
                     }
+                    ui.horizontal(|ui| {
+                        // belt is placed around the dominant body, which is the Sun for solar system
+                        let sun = dominant_body(space_simulation.bodies.masses())
+                            .map(|index| space_simulation.bodies.ids()[index]);
+
+                        if ui
+                            .add_enabled(
+                                sun.is_some(),
+                                egui::Button::new(
+                                    RichText::new("Главный пояс астероидов")
+                                        .color(Color32::LIGHT_YELLOW),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            if let Some(sun) = sun {
+                                test_particles_ev.send(SpawnTestParticlesRequest(
+                                    TestParticleRing::main_belt(sun, test_particles_form.count),
+                                ));
+                            }
+                        }
+
+                        ui.add(
+                            egui::DragValue::new(&mut test_particles_form.count)
+                                .speed(10.0)
+                                .clamp_range(1..=100_000)
+                                .suffix(" шт."),
+                        );
+                    });
                 });
 
                 ui.separator();
@@ -204,20 +279,216 @@ pub fn ui_system(
                         .color(Color32::LIGHT_BLUE),
                 );
                 ui.group(|ui| {
+                    let mode_name = |mode: &SpaceSimulationMode| match mode {
+                        SpaceSimulationMode::NBody => "Задача N тел",
+                        SpaceSimulationMode::PatchedConics => "Сопряжённые конические сечения",
+                    };
+
+                    egui::ComboBox::from_label("режим")
+                        .selected_text(mode_name(&space_simulation_params.mode))
+                        .show_ui(ui, |ui| {
+                            for mode in SpaceSimulationMode::ALL {
+                                ui.selectable_value(
+                                    &mut space_simulation_params.mode,
+                                    mode,
+                                    mode_name(&mode),
+                                );
+                            }
+                        });
+
+                    ui.horizontal(|ui| {
+                        // negative speed runs simulation backward in time
+                        ui.add(
+                            egui::DragValue::new(&mut space_simulation_params.speed)
+                                .speed(1.0)
+                                .prefix("скорость симуляции: ")
+                                .suffix(" сек."),
+                        );
+
+                        if ui
+                            .button(RichText::new("Обратить время").color(Color32::LIGHT_YELLOW))
+                            .clicked()
+                        {
+                            space_simulation_params.speed = -space_simulation_params.speed;
+                        }
+                    });
+
                     ui.add(
-                        egui::DragValue::new(&mut space_simulation_params.speed)
+                        egui::DragValue::new(&mut space_simulation_params.percision)
+                            .speed(1)
+                            .clamp_range(1..=32)
+                            .prefix("точность симуляции: "),
+                    );
+
+                    ui.add(
+                        egui::DragValue::new(&mut space_simulation_params.step)
                             .speed(1.0)
                             .clamp_range(1.0..=f64::INFINITY)
-                            .prefix("скорость симуляции: ")
+                            .prefix("шаг симуляции: ")
                             .suffix(" сек."),
                     );
 
                     ui.add(
-                        egui::DragValue::new(&mut space_simulation_params.percision)
+                        egui::DragValue::new(&mut space_simulation_params.max_steps_per_frame)
                             .speed(1)
-                            .clamp_range(1..=32)
-                            .prefix("точность симуляции: "),
+                            .clamp_range(1..=4096)
+                            .prefix("шагов за кадр не более: "),
                     );
+
+                    let integrator_name = |integrator: &SpaceSimulationIntegrator| match integrator
+                    {
+                        SpaceSimulationIntegrator::SemiImplicitEuler => "Полунеявный Эйлер",
+                        SpaceSimulationIntegrator::VelocityVerlet => "Скоростной Верле",
+                        SpaceSimulationIntegrator::RungeKutta4 => "Рунге-Кутта 4",
+                        SpaceSimulationIntegrator::Yoshida4 => "Йошида 4",
+                        SpaceSimulationIntegrator::DormandPrince45 => "Дорманд-Принс 4(5)",
+                    };
+
+                    egui::ComboBox::from_label("интегратор")
+                        .selected_text(integrator_name(&space_simulation_params.integrator))
+                        .show_ui(ui, |ui| {
+                            for integrator in SpaceSimulationIntegrator::ALL {
+                                ui.selectable_value(
+                                    &mut space_simulation_params.integrator,
+                                    integrator,
+                                    integrator_name(&integrator),
+                                );
+                            }
+                        });
+
+                    if space_simulation_params.integrator.is_adaptive() {
+                        ui.add(
+                            egui::Slider::new(&mut space_simulation_params.tolerance, 1e-14..=1e-3)
+                                .logarithmic(true)
+                                .text("допустимая погрешность"),
+                        );
+                    }
+
+                    let force_backend_name = |backend: &SpaceSimulationForceBackend| match backend {
+                        SpaceSimulationForceBackend::Direct => "Прямое суммирование",
+                        SpaceSimulationForceBackend::BarnesHut => "Барнс-Хат",
+                    };
+
+                    egui::ComboBox::from_label("расчёт сил")
+                        .selected_text(force_backend_name(&space_simulation_params.force_backend))
+                        .show_ui(ui, |ui| {
+                            for backend in SpaceSimulationForceBackend::ALL {
+                                ui.selectable_value(
+                                    &mut space_simulation_params.force_backend,
+                                    backend,
+                                    force_backend_name(&backend),
+                                );
+                            }
+                        });
+
+                    if space_simulation_params.force_backend
+                        == SpaceSimulationForceBackend::BarnesHut
+                    {
+                        ui.add(
+                            egui::Slider::new(
+                                &mut space_simulation_params.barnes_hut_theta,
+                                0.1..=1.5,
+                            )
+                            .text("угол раскрытия"),
+                        );
+                    }
+
+                    ui.checkbox(
+                        &mut space_simulation_params.post_newtonian,
+                        "релятивистская поправка (1PN)",
+                    );
+
+                    ui.checkbox(
+                        &mut space_simulation_params.zonal_harmonics,
+                        "сжатие планет (J2-J4)",
+                    );
+
+                    ui.checkbox(
+                        &mut space_simulation_params.non_gravitational,
+                        "негравитационные силы",
+                    );
+
+                    if space_simulation_params.zonal_harmonics {
+                        ui.add(
+                            egui::DragValue::new(
+                                &mut space_simulation_params.zonal_harmonics_distance,
+                            )
+                            .speed(1.0)
+                            .clamp_range(1.0..=f64::INFINITY)
+                            .prefix("в пределах: ")
+                            .suffix(" радиусов"),
+                        );
+                    }
+
+                    ui.label(format!(
+                        "шагов за кадр: {}, отклонено: {}",
+                        space_simulation.step_stats.steps, space_simulation.step_stats.rejected
+                    ));
+                });
+
+                ui.separator();
+
+                ui.label(
+                    RichText::new("Диагностика:")
+                        .heading()
+                        .color(Color32::LIGHT_BLUE),
+                );
+                ui.group(|ui| {
+                    let current = &diagnostics.current;
+                    let drift = &diagnostics.drift;
+
+                    ui.label(format!("энергия: {:.6e} Дж", current.energy()));
+                    ui.label(format!("импульс: {:.6e} кг·м/с", current.momentum.length()));
+                    ui.label(format!(
+                        "момент импульса: {:.6e} кг·м²/с",
+                        current.angular_momentum.length()
+                    ));
+                    ui.label(format!(
+                        "барицентр: ({:.3e}, {:.3e}, {:.3e}) м",
+                        current.barycenter.x, current.barycenter.y, current.barycenter.z
+                    ));
+
+                    ui.separator();
+
+                    ui.colored_label(
+                        Color32::LIGHT_YELLOW,
+                        format!("дрейф энергии: {:.3e}", drift.energy),
+                    );
+                    ui.colored_label(
+                        Color32::LIGHT_YELLOW,
+                        format!("дрейф импульса: {:.3e}", drift.momentum),
+                    );
+                    ui.colored_label(
+                        Color32::LIGHT_YELLOW,
+                        format!("дрейф момента импульса: {:.3e}", drift.angular_momentum),
+                    );
+                    ui.colored_label(
+                        Color32::LIGHT_YELLOW,
+                        format!("смещение барицентра: {:.3e} м", drift.barycenter),
+                    );
+
+                    {
+                        use egui::plot::{Line, Plot, PlotPoints};
+                        use ringbuffer::RingBufferExt;
+
+                        let points: PlotPoints = diagnostics.history.iter().copied().collect();
+
+                        Plot::new("energy_drift_plot")
+                            .height(96.0)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .show(ui, |plot_ui| {
+                                plot_ui.line(Line::new(points).color(Color32::LIGHT_YELLOW))
+                            });
+                    }
+
+                    if ui
+                        .button(RichText::new("Сбросить").color(Color32::LIGHT_YELLOW))
+                        .clicked()
+                    {
+                        diagnostics.reset();
+                    }
                 });
             });
     }
@@ -225,6 +496,34 @@ pub fn ui_system(
     egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
         ui.horizontal_centered(|ui| {
             ui.label(space_simulation.time.to_string());
+
+            if space_simulation_params.speed < 0.0 {
+                ui.label(RichText::new("обратное время").color(Color32::LIGHT_BLUE));
+            }
+
+            if space_simulation.step_stats.falling_behind {
+                ui.label(RichText::new("симуляция отстаёт").color(Color32::LIGHT_RED));
+            }
+
+            let Some((first, last)) = history.range() else { return };
+
+            let seconds = |duration: chrono::Duration| duration.num_milliseconds() as f64 / 1000.0;
+
+            // simulation may run past either end of the recorded history,
+            // the slider stretches to keep the current time within it
+            let mut offset = seconds(space_simulation.time - first);
+            let range = offset.min(0.0)..=seconds(last - first).max(offset);
+
+            ui.spacing_mut().slider_width = ui.available_width();
+
+            if ui
+                .add(egui::Slider::new(&mut offset, range).show_value(false))
+                .changed()
+            {
+                seek_ev.send(SeekSpaceSimulationRequest {
+                    time: first + chrono::Duration::milliseconds((offset * 1000.0) as i64),
+                });
+            }
         });
     });
 }
@@ -233,6 +532,17 @@ pub struct SpaceUIPlugin;
 
 impl Plugin for SpaceUIPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<ShowUI>();
+
         app.add_system(ui_system);
+        app.add_system(maneuvers_ui_system.after(ui_system));
+        app.add_system(orbital_elements_ui_system.after(ui_system));
+        app.add_system(keplerian_body_ui_system.after(ui_system));
+        app.add_system(events_ui_system.after(ui_system));
+        app.add_system(lagrange_points_ui_system.after(ui_system));
+        app.add_system(focused_body_ui_system.after(ui_system));
+        app.add_system(placement_ui_system.after(ui_system));
+        app.add_system(prediction_ui_system.after(ui_system));
+        app.add_system(scenario_ui_system.after(ui_system));
     }
 }
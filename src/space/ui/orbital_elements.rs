@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy_ecs_markers::params::Marker;
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    display::{BodyRef, BodyTrail},
+    scene::markers::FocusedBody,
+    simulation::SpaceSimulation,
+};
+
+pub fn orbital_elements_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    simulation: Res<SpaceSimulation>,
+    bodies: Query<&BodyRef>,
+    body_trails: Query<&BodyTrail>,
+    focused_body: Marker<FocusedBody>,
+) {
+    use FocusedBody::*;
+
+    if !show_ui.value {
+        return;
+    }
+
+    let Ok(BodyRef(body)) = bodies.get(focused_body[Primary]) else { return };
+    let Some(index) = simulation.bodies.index(*body) else { return };
+
+    // orbit is measured relative to the anchor of the trail, if one was chosen
+    let anchor = body_trails
+        .iter()
+        .find(|trail| trail.body == *body)
+        .and_then(|trail| trail.anchor)
+        .and_then(|anchor| simulation.bodies.index(anchor));
+
+    let orbit = simulation
+        .bodies
+        .orbital_elements(simulation.G, index, anchor);
+
+    egui::Window::new(format!("Орбита: {}", simulation.bodies.names()[index]))
+        .resizable(false)
+        .default_pos((16.0, 16.0))
+        .show(ctx.ctx_mut(), |ui| {
+            let Some((central, elements)) = orbit else {
+                ui.label("нет центрального тела");
+                return;
+            };
+
+            ui.colored_label(
+                Color32::LIGHT_BLUE,
+                format!(
+                    "относительно: {} ({})",
+                    simulation.bodies.names()[central],
+                    if anchor.is_some() {
+                        "точка отсчёта орбиты"
+                    } else {
+                        "автоматически"
+                    }
+                ),
+            );
+
+            ui.label(format!(
+                "большая полуось: {:.6e} км",
+                elements.semi_major_axis / 1000.0
+            ));
+            ui.label(format!("эксцентриситет: {:.6}", elements.eccentricity));
+            ui.label(format!(
+                "наклонение: {:.4}°",
+                elements.inclination.to_degrees()
+            ));
+            ui.label(format!(
+                "долгота восходящего узла: {:.4}°",
+                elements.longitude_of_ascending_node.to_degrees()
+            ));
+            ui.label(format!(
+                "аргумент перицентра: {:.4}°",
+                elements.argument_of_periapsis.to_degrees()
+            ));
+            ui.label(format!(
+                "истинная аномалия: {:.4}°",
+                elements.true_anomaly.to_degrees()
+            ));
+            ui.label(format!(
+                "средняя аномалия: {:.4}°",
+                elements.mean_anomaly.to_degrees()
+            ));
+
+            match elements.period {
+                Some(period) => ui.label(format!("период: {:.6} сут.", period / 86400.0)),
+                None => ui.colored_label(Color32::LIGHT_RED, "орбита незамкнута"),
+            };
+        });
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{scene::BodyPlacement, simulation::SpaceSimulation};
+
+const TEXTURES: [(&str, &str); 10] = [
+    ("textures/asteroid.jpg", "астероид"),
+    ("textures/mercury_base_color.jpg", "Меркурий"),
+    ("textures/venus_base_color.jpg", "Венера"),
+    ("textures/earth_base_color.jpg", "Земля"),
+    ("textures/moon_base_color.jpg", "Луна"),
+    ("textures/mars_base_color.jpg", "Марс"),
+    ("textures/jupiter_base_color.jpg", "Юпитер"),
+    ("textures/saturn_base_color.jpg", "Сатурн"),
+    ("textures/uranus_base_color.jpg", "Уран"),
+    ("textures/neptune_base_color.jpg", "Нептун"),
+];
+
+pub fn placement_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut placement: ResMut<BodyPlacement>,
+    simulation: Res<SpaceSimulation>,
+) {
+    if !show_ui.value {
+        return;
+    }
+
+    let placement = &mut *placement;
+
+    egui::Window::new("Создание тела")
+        .resizable(false)
+        .default_open(false)
+        .default_pos((320.0, 480.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.checkbox(&mut placement.active, "размещать щелчком");
+
+            ui.horizontal(|ui| {
+                ui.label("имя:");
+
+                let taken = simulation.bodies.find(&placement.name).is_some();
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut placement.name)
+                        .desired_width(128.0)
+                        .text_color(if taken {
+                            Color32::LIGHT_RED
+                        } else {
+                            Color32::WHITE
+                        }),
+                );
+            });
+
+            let speed = placement.mass.max(1.0) * 1e-3;
+
+            ui.add(
+                egui::DragValue::new(&mut placement.mass)
+                    .speed(speed)
+                    .clamp_range(0.0..=f64::INFINITY)
+                    .prefix("масса: ")
+                    .suffix(" кг"),
+            );
+
+            // radius is stored in meters
+            let mut radius = placement.radius / 1000.0;
+            let speed = radius.max(1.0) * 1e-2;
+
+            if ui
+                .add(
+                    egui::DragValue::new(&mut radius)
+                        .speed(speed)
+                        .clamp_range(1e-3..=f64::INFINITY)
+                        .prefix("радиус: ")
+                        .suffix(" км"),
+                )
+                .changed()
+            {
+                placement.radius = radius * 1000.0;
+            }
+
+            let texture_name = |path: &str| {
+                TEXTURES
+                    .iter()
+                    .find(|(texture, _)| *texture == path)
+                    .map_or(path.to_string(), |(_, name)| name.to_string())
+            };
+
+            egui::ComboBox::from_label("материал")
+                .selected_text(texture_name(&placement.texture))
+                .show_ui(ui, |ui| {
+                    for (texture, name) in TEXTURES {
+                        ui.selectable_value(&mut placement.texture, texture.to_string(), name);
+                    }
+                });
+
+            ui.add(
+                egui::DragValue::new(&mut placement.arrow_time)
+                    .speed(60.0)
+                    .clamp_range(1.0..=f64::INFINITY)
+                    .prefix("стрелка пройдена за: ")
+                    .suffix(" сек."),
+            );
+
+            if !placement.active {
+                return;
+            }
+
+            ui.colored_label(
+                Color32::LIGHT_BLUE,
+                "ЛКМ — точка в плоскости орбиты выбранного тела, протянуть — скорость",
+            );
+
+            let Some(velocity) = placement.velocity() else { return };
+
+            let reference = placement
+                .drag
+                .as_ref()
+                .and_then(|drag| simulation.bodies.name(drag.reference));
+
+            if let Some(reference) = reference {
+                ui.label(format!(
+                    "скорость относительно {}: {:.3} км/с",
+                    reference,
+                    velocity.length() / 1000.0
+                ));
+            }
+        });
+}
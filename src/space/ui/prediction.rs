@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::simulation::SpaceSimulationPrediction;
+
+pub fn prediction_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut prediction: ResMut<SpaceSimulationPrediction>,
+) {
+    if !show_ui.value {
+        return;
+    }
+
+    let prediction = &mut *prediction;
+
+    egui::Window::new("Прогноз траекторий")
+        .resizable(false)
+        .default_open(false)
+        .default_pos((16.0, 640.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.checkbox(&mut prediction.enabled, "показывать");
+            ui.checkbox(&mut prediction.all_bodies, "для всех тел");
+
+            // horizon is edited in days
+            let mut days = prediction.horizon / 86400.0;
+
+            let mut changed = ui
+                .add(
+                    egui::DragValue::new(&mut days)
+                        .speed(1.0)
+                        .clamp_range(1.0..=36500.0)
+                        .prefix("на: ")
+                        .suffix(" сут."),
+                )
+                .changed();
+
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut prediction.samples)
+                        .speed(4)
+                        .clamp_range(16..=8192)
+                        .prefix("точек: "),
+                )
+                .changed();
+
+            if changed {
+                prediction.horizon = days * 86400.0;
+                prediction.invalidate();
+            }
+
+            if prediction.enabled && prediction.is_computing() {
+                ui.colored_label(Color32::LIGHT_YELLOW, "вычисляется...");
+            }
+        });
+}
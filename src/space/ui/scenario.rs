@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use bevy_debug_text_overlay::screen_print;
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContext,
+};
+
+use super::ShowUI;
+use crate::space::{
+    nasa_horizons::{NasaBodyAddition, SpaceBodiesKnownDetails, SpaceBodyKnownDetailsMaterial},
+    simulation::SpaceSimulation,
+};
+
+const SAVED_BODIES: &str = "./assets/bodies/saved-bodies.json";
+
+// bodies with their maneuvers as they are at the current time of the simulation
+fn save_bodies(
+    simulation: &SpaceSimulation,
+    known_details: &SpaceBodiesKnownDetails,
+) -> anyhow::Result<()> {
+    let bodies = simulation
+        .bodies
+        .ids()
+        .iter()
+        .filter_map(|id| {
+            let name = simulation.bodies.name(*id)?.clone();
+
+            // materials of bodies are only known for the solar system
+            let material = match known_details.map.get(&name) {
+                Some(details) => details.material.clone(),
+                None => SpaceBodyKnownDetailsMaterial::TexturePath("textures/asteroid.jpg".into()),
+            };
+
+            Some(NasaBodyAddition {
+                date: simulation.time,
+                body: simulation.bodies.to_body(*id)?,
+                name,
+                material,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    std::fs::write(SAVED_BODIES, serde_json::to_string_pretty(&bodies)?)?;
+
+    Ok(())
+}
+
+fn load_bodies() -> anyhow::Result<Vec<NasaBodyAddition>> {
+    let json = std::fs::read_to_string(SAVED_BODIES)?;
+
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub fn scenario_ui_system(
+    mut ctx: ResMut<EguiContext>,
+    show_ui: Res<ShowUI>,
+    mut simulation: ResMut<SpaceSimulation>,
+    known_details: Res<SpaceBodiesKnownDetails>,
+    mut additions: EventWriter<NasaBodyAddition>,
+) {
+    if !show_ui.value {
+        return;
+    }
+
+    egui::Window::new("Сцена")
+        .resizable(false)
+        .default_open(false)
+        .default_pos((16.0, 720.0))
+        .show(ctx.ctx_mut(), |ui| {
+            ui.label(SAVED_BODIES);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !simulation.bodies.is_empty(),
+                        egui::Button::new(RichText::new("Сохранить").color(Color32::LIGHT_YELLOW)),
+                    )
+                    .clicked()
+                {
+                    match save_bodies(&simulation, &known_details) {
+                        Ok(()) => {
+                            screen_print!(sec: 3.0, col: Color::GREEN, "saved to {SAVED_BODIES}");
+                        }
+                        Err(e) => {
+                            screen_print!(sec: 3.0, col: Color::RED, "{e}");
+                        }
+                    }
+                }
+
+                if ui
+                    .button(RichText::new("Загрузить").color(Color32::LIGHT_YELLOW))
+                    .clicked()
+                {
+                    match load_bodies() {
+                        Ok(bodies) => {
+                            // bodies are saved at the same time
+                            if let Some(earliest) = bodies.iter().map(|body| body.date).min() {
+                                simulation.time = earliest;
+                            }
+
+                            additions.send_batch(bodies);
+                        }
+                        Err(e) => {
+                            screen_print!(sec: 3.0, col: Color::RED, "{e}");
+                        }
+                    }
+                }
+            });
+        });
+}